@@ -1,3 +1,5 @@
+#![allow(clippy::duplicate_mod)]
+
 use frender::prelude::*;
 
 mod lines;
@@ -30,7 +32,7 @@ impl App for AllApp {
         self.demos[self.current].render(window, canvas);
 
         if window.key_just_pressed(Key::S) {
-            // canvas.save_image_path("out/test.png");
+            // canvas.save_image_path("out/test.png").unwrap();
         }
 
         if window.key_just_pressed(Key::N) {
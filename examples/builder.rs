@@ -32,6 +32,10 @@ impl App for BuilderApp {
 
         self.builder.get_or_insert(Builder::new([mouse_pos]));
 
+        if window.mouse_just_pressed(MouseButton::Right) {
+            self.data.push(Box::new(Bucket(mouse_pos)));
+        }
+
         if let Some(mut current) = self.builder.take() {
             if window.mouse_just_pressed(MouseButton::Left) {
                 match current.commit() {
@@ -101,6 +105,16 @@ impl ShapeBuilder for Builder<2> {
     }
 }
 
+/// Fills the area under the mouse
+#[derive(Debug)]
+struct Bucket(Vec2);
+
+impl Render for Bucket {
+    fn render_context(&self, canvas: &mut dyn Canvas) {
+        canvas.draw(FloodFill::new(*self.0.x(), *self.0.y()), BLUE)
+    }
+}
+
 enum BuildDecision {
     Done(Box<dyn Render>),
     Continue(Box<dyn ShapeBuilder>),
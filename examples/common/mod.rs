@@ -1,4 +1,4 @@
-use frender::{canvas::draw_commands::{CloneCommand, Command}, prelude::*};
+use frender::{canvas::draw_commands::Command, canvas::marker::{self, Marker, Markers}, prelude::*};
 
 pub struct ScaledApp<T: App> {
     inner: T,
@@ -52,29 +52,24 @@ impl Canvas for TestingCanvas {
 
     fn size(&self) -> (usize, usize) { self.canvas.size() }
     fn size_i32(&self) -> (i32, i32) { self.canvas.size_i32() }
+    fn stride(&self) -> usize { self.canvas.stride() }
 
     fn draw_dyn(&mut self, command: &mut dyn Command) {
         command.render_canvas(self);
     }
 
-    fn marker_dyn(&mut self, marker: Box<dyn CloneCommand>) {
+    fn marker_dyn(&mut self, marker: Marker) {
         self.canvas.markers_mut().push(marker);
     }
 
-    fn markers_mut(&mut self) -> &mut Vec<Box<dyn CloneCommand>> { self.canvas.markers_mut() }
-    fn markers(&self) -> &Vec<Box<dyn CloneCommand>> { self.canvas.markers() }
+    fn markers_mut(&mut self) -> &mut Markers { self.canvas.markers_mut() }
+    fn markers(&self) -> &Markers { self.canvas.markers() }
 
     fn render_markers(&mut self) {
-        let markers = self.markers_mut().clone();
-
-        for mut marker in markers {
-            marker.render_canvas(self);
-        }
-
-        self.markers_mut().clear();
+        marker::render_markers(self);
     }
 
-    fn get_context(&mut self) -> ContextImpl {
+    fn get_context(&mut self) -> ContextImpl<'_> {
         ContextImpl::new_canvas(self)
     }
 }
@@ -26,6 +26,9 @@ impl App for FillApp {
 
         canvas.circle(5, 20, 5, RED);
         canvas.circle(60, 15, 10, WHITE.fill(RED));
+        canvas.circle(35, 30, 8, WHITE.fill(Gradient::radial(Vec2::new(35, 30), 8).stop(0.0, YELLOW).stop(1.0, RED)));
+        canvas.circle(15, 12, 6, WHITE.fill(Texture::hatch(Hatch::Diagonal, 3, GRAY)));
+        canvas.text(2, 32, "fill\ntest", WHITE);
         
         let line = Line::new(1, 1, w - 2, h - 2);
         // canvas.draw(line, WHITE);
@@ -8,6 +8,7 @@ fn main() {
     Window::new("Test", WIDTH, HEIGHT, LinesApp::new());
 }
 
+#[derive(Default)]
 pub struct LinesApp {
 
 }
@@ -60,7 +61,7 @@ impl App for LinesApp {
                 let yp = y * (h + 2) + 1;
     
                 let t = if x == 4 && y == 4 {
-                    buffer.marker(Line::new(0, 0, x * 5, y * 5), RED);
+                    buffer.marker(Line::new(0, 0, x * 5, y * 5), RED.middle());
                     Some(Transform2D::new(Vec2::new(-xp * 4, -yp * 4), Vec2::new(5, 5)))
                 } else {
                     None
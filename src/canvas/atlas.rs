@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::prelude::*;
+
+pub mod aseprite;
+
+/// Image split into named frames, and animations made of those frames
+#[derive(Debug)]
+pub struct Atlas {
+    image: CanvasImpl,
+    frames: Vec<Rect>,
+    names: HashMap<String, usize>,
+    animations: HashMap<String, Animation>,
+}
+
+impl Atlas {
+    pub fn new(image: CanvasImpl) -> Self {
+        Self {
+            image,
+            frames: Vec::new(),
+            names: HashMap::new(),
+            animations: HashMap::new(),
+        }
+    }
+
+    /// Atlas where the whole image is a grid of `width * height` frames, named after their index
+    pub fn from_grid(image: CanvasImpl, width: i32, height: i32) -> Self {
+        let (w, h) = image.size_i32();
+        let mut atlas = Self::new(image);
+
+        let (columns, rows) = (w / width.max(1), h / height.max(1));
+        atlas.add_grid("", Rect::new(0, 0, w, h), columns, rows);
+        atlas
+    }
+
+    pub fn image(&self) -> &CanvasImpl {
+        &self.image
+    }
+
+    /// Adds a frame from an explicit part of the image and returns its index
+    pub fn add_frame(&mut self, name: &str, rect: Rect) -> usize {
+        let index = self.frames.len();
+
+        self.frames.push(rect);
+        self.names.insert(name.to_string(), index);
+        index
+    }
+
+    /// Splits `area` in `columns * rows` frames, row by row, named `name` followed by their number
+    pub fn add_grid(&mut self, name: &str, area: Rect, columns: i32, rows: i32) -> Range<usize> {
+        let start = self.frames.len();
+        let (columns, rows) = (columns.max(0), rows.max(0));
+        let (width, height) = (area.size().x() / columns.max(1), area.size().y() / rows.max(1));
+
+        for row in 0..rows {
+            for column in 0..columns {
+                let rect = Rect::new(area.x1() + column * width, area.y1() + row * height, width, height);
+                let number = self.frames.len() - start;
+                self.add_frame(&format!("{name}{number}"), rect);
+            }
+        }
+
+        start..self.frames.len()
+    }
+
+    pub fn add_animation(&mut self, name: &str, animation: Animation) {
+        self.animations.insert(name.to_string(), animation);
+    }
+
+    /// Index of a named frame
+    pub fn frame(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// Part of the image covered by a frame
+    pub fn rect(&self, frame: usize) -> Option<Rect> {
+        self.frames.get(frame).copied()
+    }
+
+    pub fn frames(&self) -> &[Rect] {
+        &self.frames
+    }
+
+    pub fn animation(&self, name: &str) -> Option<&Animation> {
+        self.animations.get(name)
+    }
+}
+
+/// What happens once the last frame of an animation has been shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LoopMode {
+    /// Stays on the last frame
+    Once,
+    #[default]
+    Loop,
+    /// Plays backwards to the first frame, then forwards again
+    PingPong,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Animation {
+    frames: Vec<(usize, Duration)>,
+    mode: LoopMode,
+}
+
+impl Animation {
+    pub fn new(mode: LoopMode) -> Self {
+        Self { frames: Vec::new(), mode }
+    }
+
+    /// Animation showing each frame of `frames` for `duration`
+    pub fn from_frames(frames: impl IntoIterator<Item = usize>, duration: Duration, mode: LoopMode) -> Self {
+        Self { frames: frames.into_iter().map(|frame| (frame, duration)).collect(), mode }
+    }
+
+    /// Adds a frame of the atlas, shown for `duration`
+    pub fn frame(mut self, frame: usize, duration: Duration) -> Self {
+        self.frames.push((frame, duration));
+        self
+    }
+
+    pub fn mode(&self) -> LoopMode {
+        self.mode
+    }
+
+    pub fn frames(&self) -> &[(usize, Duration)] {
+        &self.frames
+    }
+
+    /// Time to play every frame once
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    /// Whether a `Once` animation has reached its last frame
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        self.mode == LoopMode::Once && elapsed >= self.duration()
+    }
+
+    /// Frame of the atlas shown after `elapsed`
+    pub fn frame_at(&self, elapsed: Duration) -> Option<usize> {
+        let last = self.frames.last()?.0;
+        let duration = self.duration();
+        if duration.is_zero() {
+            return Some(last);
+        }
+
+        let time = match self.mode {
+            LoopMode::Once if elapsed >= duration => return Some(last),
+            LoopMode::Once => elapsed,
+            LoopMode::Loop => Duration::from_nanos((elapsed.as_nanos() % duration.as_nanos()) as u64),
+            LoopMode::PingPong => {
+                // Going back does not repeat the last and the first frame
+                let (_, first) = self.frames[0];
+                let (_, end) = self.frames[self.frames.len() - 1];
+                let cycle = (duration * 2).saturating_sub(first + end);
+                if cycle.is_zero() {
+                    return Some(self.frames[0].0);
+                }
+
+                let time = Duration::from_nanos((elapsed.as_nanos() % cycle.as_nanos()) as u64);
+                if time < duration {
+                    time
+                } else {
+                    return self.frame_backwards(time - duration);
+                }
+            }
+        };
+
+        let mut start = Duration::ZERO;
+        for &(frame, duration) in &self.frames {
+            start += duration;
+            if time < start {
+                return Some(frame);
+            }
+        }
+
+        Some(last)
+    }
+
+    /// Frame shown `time` after starting to play backwards from the second to last frame
+    fn frame_backwards(&self, time: Duration) -> Option<usize> {
+        let mut start = Duration::ZERO;
+        for &(frame, duration) in self.frames.iter().rev().skip(1) {
+            start += duration;
+            if time < start {
+                return Some(frame);
+            }
+        }
+
+        self.frames.first().map(|(frame, _)| *frame)
+    }
+}
+
+/// Plays the animations of an atlas over time
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnimationPlayer {
+    animation: String,
+    elapsed: Duration,
+    speed: u32,
+}
+
+impl AnimationPlayer {
+    pub fn new(animation: &str) -> Self {
+        Self { animation: animation.to_string(), elapsed: Duration::ZERO, speed: 100 }
+    }
+
+    /// Switches to another animation, restarting it unless it is already playing
+    pub fn play(&mut self, animation: &str) {
+        if self.animation != animation {
+            self.animation = animation.to_string();
+            self.elapsed = Duration::ZERO;
+        }
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Playback speed in percent
+    pub fn set_speed(&mut self, speed: u32) {
+        self.speed = speed;
+    }
+
+    pub fn advance(&mut self, delta: Duration) {
+        self.elapsed += delta * self.speed / 100;
+    }
+
+    pub fn animation(&self) -> &str {
+        &self.animation
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn is_finished(&self, atlas: &Atlas) -> bool {
+        atlas.animation(&self.animation).is_some_and(|animation| animation.is_finished(self.elapsed))
+    }
+
+    /// Frame of the atlas currently shown
+    pub fn frame(&self, atlas: &Atlas) -> Option<usize> {
+        atlas.animation(&self.animation)?.frame_at(self.elapsed)
+    }
+}
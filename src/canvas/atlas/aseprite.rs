@@ -0,0 +1,491 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::Read;
+use std::rc::Rc;
+use std::time::Duration;
+
+use flate2::read::ZlibDecoder;
+
+use crate::prelude::*;
+
+const FILE_MAGIC: u16 = 0xA5E0;
+const FRAME_MAGIC: u16 = 0xF1FA;
+
+const CHUNK_OLD_PALETTE: u16 = 0x0004;
+const CHUNK_LAYER: u16 = 0x2004;
+const CHUNK_CEL: u16 = 0x2005;
+const CHUNK_TAGS: u16 = 0x2018;
+const CHUNK_PALETTE: u16 = 0x2019;
+
+const FLAG_LAYER_OPACITY: u32 = 0x01;
+const LAYER_VISIBLE: u16 = 0x01;
+const LAYER_GROUP: u16 = 1;
+const PALETTE_HAS_NAME: u16 = 0x01;
+
+/// Indexed pixels are a single byte
+const MAX_PALETTE: usize = 256;
+
+const CEL_RAW: u16 = 0;
+const CEL_LINKED: u16 = 1;
+const CEL_COMPRESSED: u16 = 2;
+
+#[derive(Debug)]
+pub enum AsepriteError {
+    Io(std::io::Error),
+    Invalid(String),
+}
+
+impl Display for AsepriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AsepriteError::Io(error) => write!(f, "could not read aseprite file: {error}"),
+            AsepriteError::Invalid(reason) => write!(f, "invalid aseprite file: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AsepriteError {}
+
+impl From<std::io::Error> for AsepriteError {
+    fn from(error: std::io::Error) -> Self {
+        AsepriteError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Layer {
+    pub name: String,
+    /// Hidden layers and the children of hidden groups are not drawn
+    pub visible: bool,
+    pub opacity: u8,
+    /// Groups only contain other layers, that have a higher level
+    pub group: bool,
+    pub level: u16,
+}
+
+/// Image of one layer in one frame
+#[derive(Debug, Clone)]
+pub struct Cel {
+    pub layer: usize,
+    pub position: Vec2,
+    pub opacity: u8,
+    /// Offset of the layer order of this cel
+    pub z_index: i16,
+    /// Linked cels share their image
+    pub image: Rc<CanvasImpl>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub duration: Duration,
+    pub cels: Vec<Cel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Forward,
+    Reverse,
+    PingPong,
+    PingPongReverse,
+}
+
+/// Named range of frames, played as an animation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tag {
+    pub name: String,
+    pub from: usize,
+    pub to: usize,
+    pub direction: Direction,
+    /// Times the animation is played, forever if 0
+    pub repeat: u16,
+}
+
+impl Tag {
+    /// Frames of one play of the animation
+    pub fn sequence(&self) -> Vec<usize> {
+        let forward: Vec<_> = (self.from..=self.to).collect();
+        let back = forward.iter().rev().skip(1).take(forward.len().saturating_sub(2));
+
+        match self.direction {
+            Direction::Forward => forward,
+            Direction::Reverse => forward.into_iter().rev().collect(),
+            Direction::PingPong => forward.iter().chain(back).copied().collect(),
+            Direction::PingPongReverse => {
+                let mut sequence: Vec<_> = forward.iter().chain(back).copied().collect();
+                sequence.reverse();
+                sequence
+            }
+        }
+    }
+}
+
+/// Sprite saved by Aseprite (.ase, .aseprite)
+#[derive(Debug, Clone)]
+pub struct Aseprite {
+    pub width: i32,
+    pub height: i32,
+    pub layers: Vec<Layer>,
+    pub frames: Vec<Frame>,
+    pub tags: Vec<Tag>,
+    /// At most 256 colors, the ones indexed pixels can refer to
+    pub palette: Vec<Color>,
+}
+
+impl Aseprite {
+    pub fn from_path(path: &str) -> Result<Self, AsepriteError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AsepriteError> {
+        let mut reader = Reader::new(bytes);
+
+        reader.skip(4)?;
+        if reader.u16()? != FILE_MAGIC {
+            return Err(AsepriteError::Invalid("not an aseprite file".into()));
+        }
+
+        let frame_count = reader.u16()?;
+        let (width, height) = (reader.u16()? as i32, reader.u16()? as i32);
+        let depth = reader.u16()?;
+        let flags = reader.u32()?;
+        reader.skip(10)?;
+        let transparent = reader.u8()?;
+        reader.skip(128 - 29)?;
+
+        if ![8, 16, 32].contains(&depth) {
+            return Err(AsepriteError::Invalid(format!("unsupported color depth {depth}")));
+        }
+
+        let mut sprite = Self { width, height, layers: Vec::new(), frames: Vec::new(), tags: Vec::new(), palette: Vec::new() };
+
+        for _ in 0..frame_count {
+            let size = reader.u32()? as usize;
+            let mut frame = reader.take(size.saturating_sub(4))?;
+            if frame.u16()? != FRAME_MAGIC {
+                return Err(AsepriteError::Invalid("bad frame magic number".into()));
+            }
+
+            let old_chunks = frame.u16()? as u32;
+            let duration = Duration::from_millis(frame.u16()? as u64);
+            frame.skip(2)?;
+            let chunks = match frame.u32()? {
+                0 => old_chunks,
+                chunks => chunks,
+            };
+
+            let mut cels = Vec::new();
+            for _ in 0..chunks {
+                let size = frame.u32()? as usize;
+                let kind = frame.u16()?;
+                let mut chunk = frame.take(size.saturating_sub(6))?;
+
+                match kind {
+                    CHUNK_LAYER => sprite.layers.push(read_layer(&mut chunk, flags & FLAG_LAYER_OPACITY != 0)?),
+                    CHUNK_CEL => {
+                        let cel = read_cel(&mut chunk, depth, &sprite, transparent)?;
+                        cels.extend(cel);
+                    }
+                    CHUNK_TAGS => sprite.tags = read_tags(&mut chunk, frame_count as usize)?,
+                    CHUNK_PALETTE => read_palette(&mut chunk, &mut sprite.palette, depth)?,
+                    CHUNK_OLD_PALETTE if sprite.palette.is_empty() => sprite.palette = read_old_palette(&mut chunk)?,
+                    _ => {}
+                }
+            }
+
+            sprite.frames.push(Frame { duration, cels });
+        }
+
+        // Indexed cels can be read before the palette, linked cels keep sharing their image
+        if depth == 8 {
+            let mut converted = HashMap::new();
+            for cel in sprite.frames.iter_mut().flat_map(|frame| frame.cels.iter_mut()) {
+                let image = converted.entry(Rc::as_ptr(&cel.image)).or_insert_with(|| Rc::new(apply_palette(&cel.image, &sprite.palette)));
+                cel.image = image.clone();
+            }
+        }
+
+        Ok(sprite)
+    }
+
+    /// Layer visibility with the visibility of the groups they are in
+    fn visible_layers(&self) -> Vec<bool> {
+        let mut groups: Vec<bool> = Vec::new();
+
+        self.layers.iter().map(|layer| {
+            groups.truncate(layer.level as usize);
+            let visible = layer.visible && groups.iter().all(|&visible| visible);
+
+            if layer.group {
+                groups.push(visible);
+            }
+            visible
+        }).collect()
+    }
+
+    /// All the visible layers of a frame drawn on top of each other
+    pub fn frame_image(&self, frame: usize) -> CanvasImpl {
+        let mut image = CanvasImpl::new(self.width.max(0) as usize, self.height.max(0) as usize);
+        let Some(frame) = self.frames.get(frame) else { return image };
+        let visible = self.visible_layers();
+
+        let mut cels: Vec<_> = frame.cels.iter().filter(|cel| visible.get(cel.layer).copied().unwrap_or(false)).collect();
+        cels.sort_by_key(|cel| (cel.layer as i32 + cel.z_index as i32, cel.z_index));
+
+        for cel in cels {
+            let opacity = (cel.opacity as u32 * self.layers[cel.layer].opacity as u32 / 255) as u8;
+            let options = ImageOption::from(cel.position).opacity(opacity as f32 / 255.0);
+
+            image.draw(&*cel.image, options);
+        }
+
+        image
+    }
+
+    /// One layer of a frame, ignoring its visibility
+    pub fn layer_image(&self, frame: usize, layer: usize) -> CanvasImpl {
+        let mut image = CanvasImpl::new(self.width.max(0) as usize, self.height.max(0) as usize);
+
+        for cel in self.frames.get(frame).into_iter().flat_map(|frame| &frame.cels).filter(|cel| cel.layer == layer) {
+            image.draw(&*cel.image, ImageOption::from(cel.position).opacity(cel.opacity as f32 / 255.0));
+        }
+
+        image
+    }
+
+    /// Atlas of every frame side by side, named after their index, and an animation for every tag
+    pub fn to_atlas(&self) -> Atlas {
+        let count = self.frames.len() as i32;
+        let mut sheet = CanvasImpl::new((self.width * count).max(0) as usize, self.height.max(0) as usize);
+
+        for i in 0..self.frames.len() {
+            sheet.draw(&self.frame_image(i), Vec2::new(i as i32 * self.width, 0));
+        }
+
+        let mut atlas = Atlas::new(sheet);
+        atlas.add_grid("", Rect::new(0, 0, self.width * count, self.height), count, 1);
+
+        for tag in &self.tags {
+            let sequence = tag.sequence();
+            let (sequence, mode) = match tag.repeat {
+                0 => (sequence, LoopMode::Loop),
+                repeat => (sequence.repeat(repeat as usize), LoopMode::Once),
+            };
+
+            let mut animation = Animation::new(mode);
+            for frame in sequence {
+                animation = animation.frame(frame, self.frames[frame].duration);
+            }
+
+            atlas.add_animation(&tag.name, animation);
+        }
+
+        atlas
+    }
+}
+
+fn read_layer(chunk: &mut Reader, has_opacity: bool) -> Result<Layer, AsepriteError> {
+    let flags = chunk.u16()?;
+    let kind = chunk.u16()?;
+    let level = chunk.u16()?;
+    chunk.skip(6)?;
+    let opacity = chunk.u8()?;
+    chunk.skip(3)?;
+
+    Ok(Layer {
+        name: chunk.string()?,
+        visible: flags & LAYER_VISIBLE != 0,
+        opacity: if has_opacity { opacity } else { 255 },
+        group: kind == LAYER_GROUP,
+        level,
+    })
+}
+
+fn read_cel(chunk: &mut Reader, depth: u16, sprite: &Aseprite, transparent: u8) -> Result<Option<Cel>, AsepriteError> {
+    let layer = chunk.u16()? as usize;
+    let position = Vec2::new(chunk.i16()? as i32, chunk.i16()? as i32);
+    let opacity = chunk.u8()?;
+    let kind = chunk.u16()?;
+    let z_index = chunk.i16()?;
+    chunk.skip(5)?;
+
+    let image = match kind {
+        CEL_RAW | CEL_COMPRESSED => {
+            let (width, height) = (chunk.u16()? as usize, chunk.u16()? as usize);
+            let size = width * height * depth as usize / 8;
+
+            let data = if kind == CEL_RAW {
+                chunk.bytes(size)?.to_vec()
+            } else {
+                let mut data = Vec::with_capacity(size);
+                ZlibDecoder::new(chunk.rest()).take(size as u64).read_to_end(&mut data)?;
+                data
+            };
+
+            if data.len() != size {
+                return Err(AsepriteError::Invalid("cel is smaller than its dimensions".into()));
+            }
+
+            Rc::new(decode_pixels(width, height, depth, &data, transparent))
+        }
+        CEL_LINKED => {
+            let frame = chunk.u16()? as usize;
+            let linked = sprite.frames.get(frame).and_then(|frame| frame.cels.iter().find(|cel| cel.layer == layer));
+
+            match linked {
+                Some(cel) => cel.image.clone(),
+                None => return Err(AsepriteError::Invalid(format!("cel linked to missing frame {frame}"))),
+            }
+        }
+        // Tilemaps are not supported
+        _ => return Ok(None),
+    };
+
+    Ok(Some(Cel { layer, position, opacity, z_index, image }))
+}
+
+/// Indexed pixels keep their index in the blue channel until the palette is known
+fn decode_pixels(width: usize, height: usize, depth: u16, data: &[u8], transparent: u8) -> CanvasImpl {
+    let buffer = match depth {
+        32 => data.chunks_exact(4).map(|p| Color::rgba(p[0], p[1], p[2], p[3])).collect(),
+        16 => data.chunks_exact(2).map(|p| Color::rgba(p[0], p[0], p[0], p[1])).collect(),
+        _ => data.iter().map(|&index| if index == transparent { TRANSPARENT } else { Color::rgb(0, 0, index) }).collect(),
+    };
+
+    CanvasImpl::new_buffer(width, height, buffer)
+}
+
+fn apply_palette(image: &CanvasImpl, palette: &[Color]) -> CanvasImpl {
+    let buffer = image.buffer().iter().map(|color| match color.is_transparent() {
+        true => TRANSPARENT,
+        false => palette.get(color.b() as usize).copied().unwrap_or(TRANSPARENT),
+    }).collect();
+
+    let (width, height) = image.size();
+    CanvasImpl::new_buffer(width, height, buffer)
+}
+
+fn read_tags(chunk: &mut Reader, frame_count: usize) -> Result<Vec<Tag>, AsepriteError> {
+    let count = chunk.u16()?;
+    chunk.skip(8)?;
+
+    (0..count).map(|_| {
+        let (from, to) = (chunk.u16()? as usize, chunk.u16()? as usize);
+        let direction = match chunk.u8()? {
+            1 => Direction::Reverse,
+            2 => Direction::PingPong,
+            3 => Direction::PingPongReverse,
+            _ => Direction::Forward,
+        };
+        let repeat = chunk.u16()?;
+        chunk.skip(10)?;
+        let name = chunk.string()?;
+
+        if from >= frame_count || to >= frame_count {
+            return Err(AsepriteError::Invalid(format!("tag {name} goes from frame {from} to {to} of {frame_count}")));
+        }
+
+        Ok(Tag { name, from, to: to.max(from), direction, repeat })
+    }).collect()
+}
+
+fn read_palette(chunk: &mut Reader, palette: &mut Vec<Color>, depth: u16) -> Result<(), AsepriteError> {
+    let size = chunk.u32()? as usize;
+    let (first, last) = (chunk.u32()? as usize, chunk.u32()? as usize);
+    chunk.skip(8)?;
+
+    // Colors past 256 can not be referenced by any pixel
+    if depth == 8 && size > MAX_PALETTE {
+        return Err(AsepriteError::Invalid(format!("indexed sprite with {size} palette colors")));
+    }
+    palette.resize(size.clamp(palette.len(), MAX_PALETTE), TRANSPARENT);
+    for i in first..=last {
+        let flags = chunk.u16()?;
+        let color = Color::rgba(chunk.u8()?, chunk.u8()?, chunk.u8()?, chunk.u8()?);
+        if flags & PALETTE_HAS_NAME != 0 {
+            chunk.string()?;
+        }
+
+        if let Some(entry) = palette.get_mut(i) {
+            *entry = color;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_old_palette(chunk: &mut Reader) -> Result<Vec<Color>, AsepriteError> {
+    let mut palette = Vec::new();
+
+    for _ in 0..chunk.u16()? {
+        let skip = chunk.u8()? as usize;
+        let count = match chunk.u8()? {
+            0 => 256,
+            count => count as usize,
+        };
+
+        if palette.len() + skip + count > MAX_PALETTE {
+            return Err(AsepriteError::Invalid("old palette with more than 256 colors".into()));
+        }
+
+        palette.resize(palette.len() + skip, TRANSPARENT);
+        for _ in 0..count {
+            palette.push(Color::rgb(chunk.u8()?, chunk.u8()?, chunk.u8()?));
+        }
+    }
+
+    Ok(palette)
+}
+
+/// Little endian values out of a byte slice
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], AsepriteError> {
+        if count > self.data.len() {
+            return Err(AsepriteError::Invalid("unexpected end of file".into()));
+        }
+
+        let (bytes, rest) = self.data.split_at(count);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn take(&mut self, count: usize) -> Result<Reader<'a>, AsepriteError> {
+        Ok(Reader::new(self.bytes(count)?))
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.data)
+    }
+
+    fn skip(&mut self, count: usize) -> Result<(), AsepriteError> {
+        self.bytes(count).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, AsepriteError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, AsepriteError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn i16(&mut self) -> Result<i16, AsepriteError> {
+        Ok(i16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, AsepriteError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String, AsepriteError> {
+        let length = self.u16()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(length)?).into_owned())
+    }
+}
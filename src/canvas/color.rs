@@ -1,8 +1,122 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+pub mod named;
+pub mod linear;
+
 #[repr(transparent)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Color(u32);
 
 impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, 255)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self((a as u32) << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32)
+    }
+
+    /// `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, the `#` is optional
+    pub fn from_hex(hex: &str) -> Result<Self, ParseColorError> {
+        let error = || ParseColorError(hex.to_string());
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+        // `from_str_radix` would also accept a sign
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        let value = u32::from_str_radix(digits, 16).map_err(|_| error())?;
+        let nibble = |i: u32| ((value >> (i * 4)) & 0xF) as u8 * 0x11;
+        let byte = |i: u32| (value >> (i * 8)) as u8;
+
+        match digits.len() {
+            3 => Ok(Self::rgb(nibble(2), nibble(1), nibble(0))),
+            4 => Ok(Self::rgba(nibble(3), nibble(2), nibble(1), nibble(0))),
+            6 => Ok(Self::rgb(byte(2), byte(1), byte(0))),
+            8 => Ok(Self::rgba(byte(3), byte(2), byte(1), byte(0))),
+            _ => Err(error()),
+        }
+    }
+
+    /// CSS color name, case insensitive
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("transparent") {
+            return Some(TRANSPARENT);
+        }
+
+        named::NAMES.iter()
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(name))
+            .map(|(_, color)| *color)
+    }
+
+    /// Hue in degrees, saturation and value between 0 and 1
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let chroma = value * saturation;
+        Self::from_hue(hue, chroma, value - chroma)
+    }
+
+    /// Hue in degrees, saturation and lightness between 0 and 1
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        Self::from_hue(hue, chroma, lightness - chroma / 2.0)
+    }
+
+    fn from_hue(hue: f32, chroma: f32, min: f32) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let channel = |c: f32| ((c + min).clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self::rgb(channel(r), channel(g), channel(b))
+    }
+
+    /// Hue in degrees, saturation and value between 0 and 1
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue();
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+
+        (hue, saturation, max)
+    }
+
+    /// Hue in degrees, saturation and lightness between 0 and 1
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min { 0.0 } else { (max - min) / (1.0 - (2.0 * lightness - 1.0).abs()) };
+
+        (hue, saturation, lightness)
+    }
+
+    /// Hue in degrees, largest and smallest channel between 0 and 1
+    fn hue(&self) -> (f32, f32, f32) {
+        let (r, g, b) = (self.r() as f32 / 255.0, self.g() as f32 / 255.0, self.b() as f32 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        (hue, max, min)
+    }
+
     pub fn as_u32(&self) -> u32 {
         self.0
     }
@@ -19,6 +133,53 @@ impl Color {
         self.0 as u8
     }
 
+    pub fn a(&self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    pub fn with_alpha(self, alpha: u8) -> Color {
+        Color(self.0 & 0x00_FF_FF_FF | (alpha as u32) << 24)
+    }
+
+    /// Linear interpolation of every channel, `t` is clamped between 0 and 1
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+        Color::rgba(channel(self.r(), other.r()), channel(self.g(), other.g()), channel(self.b(), other.b()), channel(self.a(), other.a()))
+    }
+
+    /// Increases the HSL lightness by `amount`, between 0 and 1
+    pub fn lighten(self, amount: f32) -> Color {
+        let (hue, saturation, lightness) = self.to_hsl();
+        Color::from_hsl(hue, saturation, (lightness + amount).clamp(0.0, 1.0)).with_alpha(self.a())
+    }
+
+    /// Decreases the HSL lightness by `amount`, between 0 and 1
+    pub fn darken(self, amount: f32) -> Color {
+        self.lighten(-amount)
+    }
+
+    /// Moves each channel towards `other` by `amount / 255`
+    pub fn mix(self, other: Color, amount: u8) -> Color {
+        let amount = amount as u32;
+        let channel = |shift: u32| {
+            let a = (self.0 >> shift) & 0xFF;
+            let b = (other.0 >> shift) & 0xFF;
+
+            ((a * (255 - amount) + b * amount + 127) / 255) << shift
+        };
+
+        Color(channel(24) | channel(16) | channel(8) | channel(0))
+    }
+
+    /// Multiplies every channel, white leaves the color unchanged
+    pub fn multiply(self, other: Color) -> Color {
+        let channel = |a: u8, b: u8| ((a as u32 * b as u32 + 127) / 255) as u8;
+
+        Color::rgba(channel(self.r(), other.r()), channel(self.g(), other.g()), channel(self.b(), other.b()), channel(self.a(), other.a()))
+    }
+
     pub fn is_transparent(&self) -> bool {
         self.0 & 0xFF_00_00_00 == 0
     }
@@ -30,9 +191,98 @@ impl From<u32> for Color {
     }
 }
 
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    /// Either a hex color or a CSS color name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.starts_with('#') {
+            return Self::from_hex(s);
+        }
+
+        Self::from_name(s).ok_or_else(|| ParseColorError(s.to_string()))
+    }
+}
+
+impl Display for Color {
+    /// `#rrggbb`, or `#rrggbbaa` if not opaque
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r(), self.g(), self.b())?;
+
+        if self.a() != 255 {
+            write!(f, "{:02x}", self.a())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Pixels as raw bytes, for handing them to other libraries
+pub trait ColorBytes {
+    /// The pixels in memory, BGRA on little endian machines
+    fn as_ne_bytes(&self) -> &[u8];
+    fn as_ne_bytes_mut(&mut self) -> &mut [u8];
+
+    fn to_rgba_bytes(&self) -> Vec<u8>;
+    fn to_bgra_bytes(&self) -> Vec<u8>;
+
+    /// Same as `to_bgra_bytes` without copying
+    #[cfg(target_endian = "little")]
+    fn as_bgra_bytes(&self) -> &[u8] {
+        self.as_ne_bytes()
+    }
+}
+
+impl ColorBytes for [Color] {
+    fn as_ne_bytes(&self) -> &[u8] {
+        // SAFETY: `Color` is a transparent `u32`, which has no padding and a stricter alignment than `u8`
+        unsafe { std::slice::from_raw_parts(self.as_ptr().cast(), std::mem::size_of_val(self)) }
+    }
+
+    fn as_ne_bytes_mut(&mut self) -> &mut [u8] {
+        // SAFETY: every byte pattern is a valid `u32`
+        unsafe { std::slice::from_raw_parts_mut(self.as_mut_ptr().cast(), std::mem::size_of_val(self)) }
+    }
+
+    fn to_rgba_bytes(&self) -> Vec<u8> {
+        self.iter().flat_map(|color| [color.r(), color.g(), color.b(), color.a()]).collect()
+    }
+
+    fn to_bgra_bytes(&self) -> Vec<u8> {
+        self.iter().flat_map(|color| [color.b(), color.g(), color.r(), color.a()]).collect()
+    }
+}
+
+impl Color {
+    /// Colors out of RGBA bytes, a trailing incomplete pixel is ignored
+    pub fn from_rgba_bytes(bytes: &[u8]) -> Vec<Color> {
+        bytes.chunks_exact(4).map(|p| Color::rgba(p[0], p[1], p[2], p[3])).collect()
+    }
+
+    pub fn from_bgra_bytes(bytes: &[u8]) -> Vec<Color> {
+        bytes.chunks_exact(4).map(|p| Color::rgba(p[2], p[1], p[0], p[3])).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError(String);
+
+impl Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid color `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
 //                                      A  R  G  B
 pub const TRANSPARENT:  Color = Color(0x00_00_00_00);
 pub const WHITE:        Color = Color(0xFF_FF_FF_FF);
 pub const RED:          Color = Color(0xFF_FF_00_00);
+pub const LIME:         Color = Color(0xFF_00_FF_00);
+pub const BLUE:         Color = Color(0xFF_00_00_FF);
+pub const YELLOW:       Color = Color(0xFF_FF_FF_00);
 pub const GRAY:         Color = Color(0xFF_33_33_33);
 pub const BLACK:        Color = Color(0xFF_00_00_00);
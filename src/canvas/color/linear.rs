@@ -0,0 +1,188 @@
+use std::ops::{Add, AddAssign, Mul, MulAssign};
+use std::sync::OnceLock;
+
+use super::Color;
+
+/// Color with linear-light channels, values above 1 are allowed for HDR
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct LinearColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl LinearColor {
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn lerp(self, other: LinearColor, t: f32) -> LinearColor {
+        self * (1.0 - t) + other * t
+    }
+
+    /// Clamps the channels and encodes them back to sRGB
+    pub fn to_srgb(self) -> Color {
+        let channel = |c: f32| (linear_to_srgb(c.clamp(0.0, 1.0)) * 255.0).round() as u8;
+        let alpha = (self.a.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        Color::rgba(channel(self.r), channel(self.g), channel(self.b), alpha)
+    }
+
+    /// Maps HDR values to the displayable range and encodes them to sRGB
+    pub fn tone_map(self, tone_map: ToneMap) -> Color {
+        let map = |c: f32| match tone_map {
+            ToneMap::Clamp => c,
+            ToneMap::Reinhard => c / (1.0 + c),
+            ToneMap::Aces => (c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14),
+        };
+
+        LinearColor::new(map(self.r), map(self.g), map(self.b), self.a).to_srgb()
+    }
+}
+
+impl From<Color> for LinearColor {
+    fn from(color: Color) -> Self {
+        let table = srgb_table();
+
+        Self {
+            r: table[color.r() as usize],
+            g: table[color.g() as usize],
+            b: table[color.b() as usize],
+            a: color.a() as f32 / 255.0,
+        }
+    }
+}
+
+impl From<LinearColor> for Color {
+    fn from(color: LinearColor) -> Self {
+        color.to_srgb()
+    }
+}
+
+impl Add for LinearColor {
+    type Output = LinearColor;
+
+    fn add(self, rhs: LinearColor) -> LinearColor {
+        LinearColor::new(self.r + rhs.r, self.g + rhs.g, self.b + rhs.b, self.a + rhs.a)
+    }
+}
+
+impl AddAssign for LinearColor {
+    fn add_assign(&mut self, rhs: LinearColor) {
+        *self = *self + rhs;
+    }
+}
+
+impl Mul<f32> for LinearColor {
+    type Output = LinearColor;
+
+    fn mul(self, rhs: f32) -> LinearColor {
+        LinearColor::new(self.r * rhs, self.g * rhs, self.b * rhs, self.a * rhs)
+    }
+}
+
+impl MulAssign<f32> for LinearColor {
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul for LinearColor {
+    type Output = LinearColor;
+
+    fn mul(self, rhs: LinearColor) -> LinearColor {
+        LinearColor::new(self.r * rhs.r, self.g * rhs.g, self.b * rhs.b, self.a * rhs.a)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ToneMap {
+    #[default]
+    Clamp,
+    Reinhard,
+    /// Narkowicz's fit of the ACES filmic curve
+    Aces,
+}
+
+/// Space in which colors are interpolated when blending, in gradients and when filtering images
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorSpace {
+    /// Directly on the stored values, fast but midtones come out too dark
+    #[default]
+    Srgb,
+    Linear,
+}
+
+impl ColorSpace {
+    /// Channels between 0 and 1 in this space, for doing math on colors
+    pub fn decode(self, color: Color) -> LinearColor {
+        match self {
+            ColorSpace::Srgb => LinearColor::new(color.r() as f32, color.g() as f32, color.b() as f32, color.a() as f32) * (1.0 / 255.0),
+            ColorSpace::Linear => color.into(),
+        }
+    }
+
+    pub fn encode(self, color: LinearColor) -> Color {
+        match self {
+            ColorSpace::Srgb => {
+                let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+                Color::rgba(channel(color.r), channel(color.g), channel(color.b), channel(color.a))
+            }
+            ColorSpace::Linear => color.to_srgb(),
+        }
+    }
+
+    pub fn lerp(self, from: Color, to: Color, t: f32) -> Color {
+        match self {
+            ColorSpace::Srgb => from.lerp(to, t),
+            ColorSpace::Linear => LinearColor::from(from).lerp(to.into(), t).to_srgb(),
+        }
+    }
+
+    /// Moves `from` towards `to` by `amount / 255`
+    pub fn mix(self, from: Color, to: Color, amount: u8) -> Color {
+        match self {
+            ColorSpace::Srgb => from.mix(to, amount),
+            ColorSpace::Linear => self.lerp(from, to, amount as f32 / 255.0),
+        }
+    }
+
+    /// Draws `source` over `destination`, weighted by the alpha of both
+    pub fn over(self, source: Color, destination: Color) -> Color {
+        match source.a() {
+            255 => return source,
+            0 => return destination,
+            _ => {}
+        }
+
+        let (s, d) = (self.decode(source), self.decode(destination));
+        let alpha = s.a + d.a * (1.0 - s.a);
+        let color = (s * s.a + d * (d.a * (1.0 - s.a))) * (1.0 / alpha);
+
+        self.encode(LinearColor { a: alpha, ..color })
+    }
+}
+
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Decoding an 8 bit channel is a lookup
+fn srgb_table() -> &'static [f32; 256] {
+    static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+
+    TABLE.get_or_init(|| std::array::from_fn(|i| srgb_to_linear(i as f32 / 255.0)))
+}
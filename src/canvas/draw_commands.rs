@@ -2,6 +2,9 @@ pub mod line;
 pub mod background;
 pub mod image;
 pub mod circle;
+pub mod text;
+pub mod flood_fill;
+pub mod marker_style;
 
 use crate::prelude::*;
 use std::fmt::Debug;
@@ -25,7 +25,7 @@ impl Command for BackgroundCommand {
         let (w, h) = canvas.size_i32();
 
         for y in 0..h {
-            canvas.pixels_mut(0..w, y).color(self.0);
+            canvas.set_pixels(0..w, y, self.0);
         }
     }
 }
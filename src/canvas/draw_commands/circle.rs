@@ -2,25 +2,25 @@ use crate::prelude::*;
 
 use super::{Command, DrawCommand};
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CircleOption {
     stroke_color: Color,
-    fill_color: Color,
+    fill: Paint,
 }
 
 impl<C: Into<Color>> From<C> for CircleOption {
     fn from(from: C) -> Self {
         Self {
             stroke_color: from.into(),
-            fill_color: TRANSPARENT,
+            fill: Paint::Solid(TRANSPARENT),
         }
     }
 }
 
 pub trait CircleOptionTrait: Into<CircleOption> {
-    fn fill(self, color: impl Into<Color>) -> CircleOption {
+    fn fill(self, paint: impl Into<Paint>) -> CircleOption {
         let mut options = self.into();
-        options.fill_color = color.into();
+        options.fill = paint.into();
 
         options
     }
@@ -28,7 +28,7 @@ pub trait CircleOptionTrait: Into<CircleOption> {
 
 impl<T: Into<CircleOption>> CircleOptionTrait for T {}
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct CircleCommand {
     circle: Circle,
     options: CircleOption,
@@ -37,6 +37,7 @@ pub struct CircleCommand {
 impl Transform for CircleCommand {
     fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
         self.circle.transform(transform);
+        self.options.fill.transform(transform);
     }
 }
 
@@ -58,33 +59,36 @@ impl Command for CircleCommand {
         // Decision parameter.
         let mut p = 1 - self.circle.radius();
         let color = self.options.stroke_color;
-        let fill = self.options.fill_color;
+        let fill = &self.options.fill;
+
+        // Half width of the filled span of each row, from the top. Rows are found several times
+        // but only filled once, so semi-transparent paints are not blended twice.
+        let radius = self.circle.radius().max(0);
+        let mut spans = vec![-1; radius as usize * 2 + 1];
+        let mut span = |row: i32, half: i32| {
+            if let Some(span) = spans.get_mut((row + radius) as usize) {
+                *span = (*span).max(half);
+            }
+        };
+
+        // The outline is drawn after the fill, over it
+        let mut outline = Vec::new();
     
         // Iterate through the first octant.
         while x <= y {
     
             // Each computed (x,y) produces eight symmetrical points:
-            canvas.pixel_mut(cx + x, cy + y).color(color);
-            canvas.pixel_mut(cx - x, cy + y).color(color);
-            canvas.pixel_mut(cx + x, cy - y).color(color);
-            canvas.pixel_mut(cx - x, cy - y).color(color);
-
-
-            canvas.pixel_mut(cx + y, cy + x).color(color);
-            canvas.pixel_mut(cx - y, cy + x).color(color);
-            canvas.pixel_mut(cx + y, cy - x).color(color);
-            canvas.pixel_mut(cx - y, cy - x).color(color);
+            outline.extend([(cx + x, cy + y), (cx - x, cy + y), (cx + x, cy - y), (cx - x, cy - y)]);
+            outline.extend([(cx + y, cy + x), (cx - y, cy + x), (cx + y, cy - x), (cx - y, cy - x)]);
     
-            if fill != TRANSPARENT {
-                // For the horizontal line at cy + y, fill between (cx - x + 1) and (cx + x - 1).
-                if x < y { // Only fill if there's an interval.
-                    canvas.pixels_mut((cx - x)..(cx + x + 1), cy + y - 1).color(fill);
-                    canvas.pixels_mut((cx - x)..(cx + x + 1), cy - y + 1).color(fill);
-
-                    // // Similarly, fill between the horizontal extents for the points when x and y swap.
-                    canvas.pixels_mut((cx - y + 1)..(cx + y), cy + x).color(fill);
-                    canvas.pixels_mut((cx - y + 1)..(cx + y), cy - x).color(fill);
-                }
+            // For the horizontal line at cy + y, fill between (cx - x) and (cx + x).
+            if x < y { // Only fill if there's an interval.
+                span(y - 1, x);
+                span(-y + 1, x);
+
+                // Similarly, fill between the horizontal extents for the points when x and y swap.
+                span(x, y - 1);
+                span(-x, y - 1);
             }
 
             // Update decision parameter and coordinates.
@@ -96,5 +100,15 @@ impl Command for CircleCommand {
             }
             x += 1;
         }
+
+        if !fill.is_transparent() {
+            for (row, &half) in spans.iter().enumerate().filter(|(_, half)| **half >= 0) {
+                fill.fill_span(canvas, (cx - half)..(cx + half + 1), cy + row as i32 - radius);
+            }
+        }
+
+        for (x, y) in outline {
+            canvas.set_pixel(x, y, color);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,163 @@
+use std::ops::Range;
+
+use crate::prelude::*;
+
+use super::{Command, DrawCommand};
+
+/// Which neighbours of a pixel belong to the same region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Connectivity {
+    /// Left, right, up and down
+    #[default]
+    Four,
+    /// Diagonals too, the fill leaks through diagonal gaps
+    Eight,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloodFillOption {
+    paint: Paint,
+    tolerance: u8,
+    connectivity: Connectivity,
+}
+
+impl<C: Into<Color>> From<C> for FloodFillOption {
+    fn from(from: C) -> Self {
+        Self {
+            paint: Paint::Solid(from.into()),
+            tolerance: 0,
+            connectivity: Connectivity::default(),
+        }
+    }
+}
+
+pub trait FloodFillOptionTrait: Into<FloodFillOption> {
+    /// Fills with a gradient or a texture instead of a color
+    fn paint(self, paint: impl Into<Paint>) -> FloodFillOption {
+        let mut options = self.into();
+        options.paint = paint.into();
+
+        options
+    }
+
+    /// Largest difference of every channel with the seed color that is still filled
+    fn tolerance(self, tolerance: u8) -> FloodFillOption {
+        let mut options = self.into();
+        options.tolerance = tolerance;
+
+        options
+    }
+
+    fn connectivity(self, connectivity: Connectivity) -> FloodFillOption {
+        let mut options = self.into();
+        options.connectivity = connectivity;
+
+        options
+    }
+}
+
+impl<T: Into<FloodFillOption>> FloodFillOptionTrait for T {}
+
+/// Fills the region around the seed that has the color of the seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FloodFill {
+    seed: Vec2,
+}
+
+impl FloodFill {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { seed: Vec2::new(x, y) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FloodFillCommand {
+    seed: Vec2,
+    options: FloodFillOption,
+}
+
+impl DrawCommand for FloodFill {
+    type Options = FloodFillOption;
+    type Command = FloodFillCommand;
+
+    fn into_renderable(self, options: impl Into<Self::Options>) -> Self::Command {
+        FloodFillCommand { seed: self.seed, options: options.into() }
+    }
+}
+
+impl FloodFillCommand {
+    /// Horizontal runs of the region, found before anything is painted
+    fn spans(&self, canvas: &dyn Canvas) -> Vec<(Range<i32>, i32)> {
+        let (x, y) = self.seed.to_tuple();
+        let Some(target) = canvas.pixel(x, y) else { return Vec::new() };
+
+        let (w, h) = canvas.size_i32();
+        let mut visited = vec![false; (w * h) as usize];
+        let fillable = |visited: &[bool], x: i32, y: i32| {
+            let Some(color) = canvas.pixel(x, y) else { return false };
+            !visited[(y * w + x) as usize] && matches(color, target, self.options.tolerance)
+        };
+
+        let reach = match self.options.connectivity {
+            Connectivity::Four => 0,
+            Connectivity::Eight => 1,
+        };
+
+        let mut spans = Vec::new();
+        let mut stack = vec![(x, y)];
+
+        while let Some((x, y)) = stack.pop() {
+            if !fillable(&visited, x, y) {
+                continue;
+            }
+
+            let (mut left, mut right) = (x, x);
+            while fillable(&visited, left - 1, y) {
+                left -= 1;
+            }
+            while fillable(&visited, right + 1, y) {
+                right += 1;
+            }
+
+            let row = (y * w) as usize;
+            visited[row + left as usize..=row + right as usize].fill(true);
+            spans.push((left..right + 1, y));
+
+            // Seed the start of every run touching the span in the rows above and below
+            for y in [y - 1, y + 1] {
+                let mut inside = false;
+
+                for x in left - reach..=right + reach {
+                    let fill = fillable(&visited, x, y);
+                    if fill && !inside {
+                        stack.push((x, y));
+                    }
+                    inside = fill;
+                }
+            }
+        }
+
+        spans
+    }
+}
+
+impl Command for FloodFillCommand {
+    fn render_canvas(&mut self, canvas: &mut dyn Canvas) {
+        for (x, y) in self.spans(canvas) {
+            self.options.paint.fill_span(canvas, x, y);
+        }
+    }
+}
+
+impl Transform for FloodFillCommand {
+    fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
+        self.seed.transform(transform);
+        self.options.paint.transform(transform);
+    }
+}
+
+fn matches(color: Color, target: Color, tolerance: u8) -> bool {
+    let close = |a: u8, b: u8| a.abs_diff(b) <= tolerance;
+
+    close(color.a(), target.a()) && close(color.r(), target.r()) && close(color.g(), target.g()) && close(color.b(), target.b())
+}
@@ -1,75 +1,366 @@
 use crate::prelude::*;
 
-use super::{CloneCommand, Command, DrawCommand};
+use crate::canvas::marker::Markers;
+
+use super::{Command, DrawCommand};
+
+/// How source pixels are sampled when the image is scaled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Filter {
+    /// Closest pixel, keeps pixel art sharp
+    #[default]
+    Nearest,
+    /// Interpolates the 4 closest pixels, for smooth zooming
+    Bilinear,
+    /// Averages all the pixels covered, for downscaling
+    Box,
+}
+
+/// How the alpha of the image is written to the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AlphaMode {
+    /// Replaces the canvas pixels, alpha included
+    Copy,
+    /// Copies the pixels that are not fully transparent
+    Skip,
+    /// Draws the image over the canvas
+    #[default]
+    Blend,
+}
 
 #[derive(Debug)]
 pub struct ImageOption {
     destination: Vec2<i32>,
-    scaling: Vec2<i32>,
+    scaling: Vec2<f32>,
+    size: Option<Vec2<f32>>,
+    source: Option<Rect>,
+    filter: Filter,
+    color_space: ColorSpace,
+    flip_x: bool,
+    flip_y: bool,
+    quarter_turns: u8,
+    angle: f32,
+    pivot: Option<Vec2<f32>>,
+    alpha: AlphaMode,
+    color_key: Option<Color>,
+    tint: Color,
 }
 
 impl ImageOption {
     pub fn scaling(mut self, scale: i32) -> Self {
-        self.scaling = Vec2::new(scale, scale);
+        self.scaling = Vec2::new(scale as f32, scale as f32);
+        self.size = None;
+        self
+    }
+
+    /// Fractional and non-uniform scaling
+    pub fn scale(mut self, x: f32, y: f32) -> Self {
+        self.scaling = Vec2::new(x, y);
+        self.size = None;
+        self
+    }
+
+    /// Stretches the image to `width * height` destination pixels
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.size = Some(Vec2::new(width, height));
+        self
+    }
+
+    /// Only draws the `source` part of the image, like one sprite out of a sheet
+    pub fn source(mut self, source: Rect) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Space in which the bilinear and box filters average colors
+    pub fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    /// Mirrors the image left to right
+    pub fn flip_horizontal(mut self) -> Self {
+        self.flip_x = !self.flip_x;
+        self
+    }
+
+    /// Mirrors the image top to bottom
+    pub fn flip_vertical(mut self) -> Self {
+        self.flip_y = !self.flip_y;
+        self
+    }
+
+    /// Rotates by `turns` quarter turns clockwise, without any resampling
+    pub fn rotate_quarter(mut self, turns: i32) -> Self {
+        self.quarter_turns = (self.quarter_turns as i32 + turns).rem_euclid(4) as u8;
+        self
+    }
+
+    /// Rotates clockwise by `angle` radians around the pivot, sampled with the filter
+    pub fn rotate(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    /// Point that stays in place when rotating, in source pixels, the center by default
+    pub fn pivot(mut self, x: f32, y: f32) -> Self {
+        self.pivot = Some(Vec2::new(x, y));
+        self
+    }
+
+    pub fn alpha(mut self, alpha: AlphaMode) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Pixels of this color are transparent, whatever their alpha
+    pub fn color_key(mut self, key: impl Into<Color>) -> Self {
+        self.color_key = Some(key.into());
+        self
+    }
+
+    /// Multiplies every pixel by `tint`, alpha included
+    pub fn tint(mut self, tint: impl Into<Color>) -> Self {
+        self.tint = tint.into();
+        self
+    }
+
+    /// Multiplies the alpha of every pixel, between 0 and 1
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        let alpha = self.tint.a() as f32 * opacity.clamp(0.0, 1.0);
+        self.tint = self.tint.with_alpha(alpha.round() as u8);
         self
     }
 }
 
 impl From<Vec2<i32>> for ImageOption {
     fn from(destination: Vec2<i32>) -> Self {
-        Self { 
+        Self {
             destination,
             scaling: Vec2::one(),
+            size: None,
+            source: None,
+            filter: Filter::default(),
+            color_space: ColorSpace::default(),
+            flip_x: false,
+            flip_y: false,
+            quarter_turns: 0,
+            angle: 0.0,
+            pivot: None,
+            alpha: AlphaMode::default(),
+            color_key: None,
+            tint: WHITE,
         }
     }
 }
 
+impl From<Rect> for ImageOption {
+    fn from(rect: Rect) -> Self {
+        let (width, height) = rect.size().to_tuple();
+        Self::from(*rect.position()).size(width as f32, height as f32)
+    }
+}
+
 #[derive(Debug)]
 pub struct ImageCommand<'a> {
     image: &'a dyn Canvas,
     options: ImageOption,
-    markers: Vec<Box<dyn CloneCommand>>,
+    markers: Markers,
+
+    // Part of the image that is drawn and the part of it that exists
+    source: Rect,
+    clip: Option<Rect>,
 }
 
-impl<'a, T: Canvas> DrawCommand for &'a T {
-    type Options = ImageOption;
-    type Command = ImageCommand<'a>;
+impl<'a> ImageCommand<'a> {
+    fn new(image: &'a dyn Canvas, mut options: ImageOption) -> Self {
+        let (w, h) = image.size_i32();
+        let bounds = Rect::new(0, 0, w, h);
+        let source = options.source.unwrap_or(bounds);
 
-    fn into_renderable(self, options: impl Into<Self::Options>) -> Self::Command {
-        let options = options.into();
-        let mut markers = self.markers().clone();
+        // An explicit size is just a scaling that depends on the source
+        if let Some(size) = options.size.take() {
+            let (w, h) = source.size().to_tuple();
+            options.scaling = Vec2::new(size.x() / w.max(1) as f32, size.y() / h.max(1) as f32);
+        }
+
+        let mut command = Self { image, options, markers: Markers::new(), source, clip: source.intersection(&bounds) };
 
-        let transform = Transform2D::new(options.destination, options.scaling);
+        // The markers are rendered later on the destination, at its resolution
+        let mut markers = image.markers().snapshot();
         for marker in &mut markers {
-            marker.transform(&transform);
+            marker.transform(&Placement { command: &command });
+        }
+
+        command.markers = markers;
+        command
+    }
+
+    /// Pixel of the image, with the color key applied
+    fn pixel(&self, x: i32, y: i32) -> Option<Color> {
+        let color = self.image.pixel(x, y)?;
+
+        match self.options.color_key {
+            Some(key) if key.with_alpha(255) == color.with_alpha(255) => Some(TRANSPARENT),
+            _ => Some(color),
+        }
+    }
+
+    /// Writes a sampled color to the canvas
+    fn put(&self, canvas: &mut dyn Canvas, x: i32, y: i32, color: Color) {
+        let color = if self.options.tint == WHITE { color } else { color.multiply(self.options.tint) };
+        let Some(pixel) = canvas.pixel(x, y) else { return };
+
+        match self.options.alpha {
+            AlphaMode::Copy => canvas.set_pixel(x, y, color),
+            AlphaMode::Skip if color.is_transparent() => {}
+            AlphaMode::Skip => canvas.set_pixel(x, y, color),
+            AlphaMode::Blend => canvas.set_pixel(x, y, self.options.color_space.over(color, pixel)),
+        }
+    }
+
+    fn is_transformed(&self) -> bool {
+        self.options.flip_x || self.options.flip_y || self.options.quarter_turns != 0 || self.options.angle != 0.0
+    }
+
+    /// Size of the scaled source, before the quarter turns
+    fn scaled_size(&self) -> (f32, f32) {
+        let (w, h) = self.source.size().to_tuple();
+        (w as f32 * self.options.scaling.x(), h as f32 * self.options.scaling.y())
+    }
+
+    /// Source point `u, v` to destination point, relative to the destination and without the free rotation
+    fn place(&self, u: f32, v: f32) -> (f32, f32) {
+        let (w, h) = self.scaled_size();
+        let (mut x, mut y) = (u * self.options.scaling.x(), v * self.options.scaling.y());
+
+        if self.options.flip_x {
+            x = w - x;
+        }
+        if self.options.flip_y {
+            y = h - y;
         }
 
-        ImageCommand {
-            image: self,
-            options: options,
-            markers,
+        match self.options.quarter_turns {
+            1 => (h - y, x),
+            2 => (w - x, h - y),
+            3 => (y, w - x),
+            _ => (x, y),
         }
     }
+
+    /// Inverse of `place`
+    fn unplace(&self, x: f32, y: f32) -> (f32, f32) {
+        let (w, h) = self.scaled_size();
+
+        let (mut x, mut y) = match self.options.quarter_turns {
+            1 => (y, h - x),
+            2 => (w - x, h - y),
+            3 => (w - y, x),
+            _ => (x, y),
+        };
+
+        if self.options.flip_x {
+            x = w - x;
+        }
+        if self.options.flip_y {
+            y = h - y;
+        }
+
+        (x / self.options.scaling.x(), y / self.options.scaling.y())
+    }
+
+    /// Pivot of the free rotation, relative to the destination
+    fn pivot(&self) -> (f32, f32) {
+        let (w, h) = self.source.size().to_tuple();
+        let pivot = self.options.pivot.unwrap_or(Vec2::new(w as f32 / 2.0, h as f32 / 2.0));
+
+        self.place(*pivot.x(), *pivot.y())
+    }
+
+    /// Rotates `x, y` by `angle` around the pivot
+    fn rotate(&self, x: f32, y: f32, angle: f32) -> (f32, f32) {
+        let (px, py) = self.pivot();
+        let (sin, cos) = angle.sin_cos();
+        let (dx, dy) = (x - px, y - py);
+
+        (px + dx * cos - dy * sin, py + dx * sin + dy * cos)
+    }
+
+    /// Color at the source point `u, v`, relative to the top left corner of the source.
+    /// Filters never read outside of the source, so neighbouring sprites do not bleed in.
+    fn sample(&self, u: f32, v: f32) -> Option<Color> {
+        let (sx, sy) = self.options.scaling.to_tuple();
+        let clip = self.clip?;
+        let (ox, oy) = self.source.position().to_tuple();
+
+        if !clip.contains(ox + u.floor() as i32, oy + v.floor() as i32) {
+            return None;
+        }
+
+        let pixel = |u: i32, v: i32| {
+            let u = (ox + u).clamp(clip.x1(), clip.x2() - 1);
+            let v = (oy + v).clamp(clip.y1(), clip.y2() - 1);
+            self.pixel(u, v).unwrap_or(TRANSPARENT)
+        };
+
+        let color = match self.options.filter {
+            Filter::Nearest => pixel(u.floor() as i32, v.floor() as i32),
+            Filter::Bilinear => {
+                let (u, v) = (u - 0.5, v - 0.5);
+                let (u0, v0) = (u.floor(), v.floor());
+                let (fu, fv) = (u - u0, v - v0);
+                let (u0, v0) = (u0 as i32, v0 as i32);
+
+                let mut sum = Average::new(self.options.color_space);
+                sum.add(pixel(u0, v0), (1.0 - fu) * (1.0 - fv));
+                sum.add(pixel(u0 + 1, v0), fu * (1.0 - fv));
+                sum.add(pixel(u0, v0 + 1), (1.0 - fu) * fv);
+                sum.add(pixel(u0 + 1, v0 + 1), fu * fv);
+                sum.color()
+            }
+            Filter::Box => {
+                // Area of the source covered by the destination pixel
+                let (u1, u2) = (u - 0.5 / sx, u + 0.5 / sx);
+                let (v1, v2) = (v - 0.5 / sy, v + 0.5 / sy);
+
+                let mut sum = Average::new(self.options.color_space);
+                for v in v1.floor() as i32..v2.ceil() as i32 {
+                    let height = (v2.min(v as f32 + 1.0) - v1.max(v as f32)).max(0.0);
+
+                    for u in u1.floor() as i32..u2.ceil() as i32 {
+                        let width = (u2.min(u as f32 + 1.0) - u1.max(u as f32)).max(0.0);
+                        sum.add(pixel(u, v), width * height);
+                    }
+                }
+                sum.color()
+            }
+        };
+
+        Some(color)
+    }
 }
 
-impl<'a> DrawCommand for &'a dyn Canvas {
+impl<'a, T: Canvas> DrawCommand for &'a T {
     type Options = ImageOption;
     type Command = ImageCommand<'a>;
 
     fn into_renderable(self, options: impl Into<Self::Options>) -> Self::Command {
-        let options = options.into();
-        let mut markers = self.markers().clone();
+        ImageCommand::new(self, options.into())
+    }
+}
 
-        let transform = Transform2D::new(options.destination, options.scaling);
-        for marker in &mut markers {
-            marker.transform(&transform);
-        }
+impl<'a> DrawCommand for &'a dyn Canvas {
+    type Options = ImageOption;
+    type Command = ImageCommand<'a>;
 
-        ImageCommand {
-            image: self,
-            options: options,
-            markers,
-        }
+    fn into_renderable(self, options: impl Into<Self::Options>) -> Self::Command {
+        ImageCommand::new(self, options.into())
     }
 }
 
@@ -78,33 +369,46 @@ impl<'a> Command for ImageCommand<'a> {
     fn render_canvas(&mut self, canvas: &mut dyn Canvas) {
         canvas.markers_mut().append(&mut self.markers);
 
-        let (width, height) = self.image.size();
+        let (width, height) = self.source.size().to_tuple();
+        let (ox, oy) = self.source.position().to_tuple();
         let (x, y) = self.options.destination.as_tuple();
 
-        if self.options.scaling.is_one() {
-            for i in 0..width as i32 {
-                for j in 0..height as i32 {
-                    let Some(color) = self.image.pixel(i, j) else { continue };
-                    let Some(pixel) = canvas.pixel_mut(x + i, y + j) else { continue };
-    
-                    *pixel = color;
+        if self.options.scaling.is_one() && !self.is_transformed() {
+            for i in 0..width {
+                for j in 0..height {
+                    let Some(color) = self.pixel(ox + i, oy + j) else { continue };
+                    self.put(canvas, x + i, y + j, color);
                 }
             }
 
             return
         }
 
-        let scale = self.options.scaling;
-        for i in 0..width as i32 {
-            for j in 0..height as i32 {
-                let Some(color) = self.image.pixel(i, j) else { continue };
-                for dy in 0..*scale.y() {
-                    let x = x + i * *scale.x()..x + i * *scale.x() + *scale.x();
-                    canvas.pixels_mut(x, y + j * *scale.y() + dy).color(color);
-                }
+        let (sx, sy) = self.options.scaling.to_tuple();
+        if width <= 0 || height <= 0 || sx <= 0.0 || sy <= 0.0 {
+            return;
+        }
+
+        // Bounding box of the drawn image, only the pixels inside of the canvas are sampled
+        let (w, h) = self.scaled_size();
+        let (w, h) = if self.options.quarter_turns % 2 == 1 { (h, w) } else { (w, h) };
+        let corners = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)].map(|(cx, cy)| self.rotate(cx, cy, self.options.angle));
+
+        let left = corners.iter().map(|c| c.0).fold(f32::INFINITY, f32::min).round() as i32;
+        let right = corners.iter().map(|c| c.0).fold(f32::NEG_INFINITY, f32::max).round() as i32;
+        let top = corners.iter().map(|c| c.1).fold(f32::INFINITY, f32::min).round() as i32;
+        let bottom = corners.iter().map(|c| c.1).fold(f32::NEG_INFINITY, f32::max).round() as i32;
+
+        let (cw, ch) = canvas.size_i32();
+        for j in top.max(-y)..bottom.min(ch - y) {
+            for i in left.max(-x)..right.min(cw - x) {
+                let (px, py) = self.rotate(i as f32 + 0.5, j as f32 + 0.5, -self.options.angle);
+                let (u, v) = self.unplace(px, py);
+
+                let Some(color) = self.sample(u, v) else { continue };
+                self.put(canvas, x + i, y + j, color);
             }
         }
-        
     }
 }
 
@@ -115,6 +419,92 @@ impl<'a> Transform for ImageCommand<'a> {
         }
 
         self.options.destination.transform(transform);
-        self.options.scaling.transform(&Transform2D::new_scaling(transform.scaling()));
+
+        // The scaling is applied before the quarter turns
+        let (mut sx, mut sy) = transform.scaling().to_tuple();
+        if self.options.quarter_turns % 2 == 1 {
+            std::mem::swap(&mut sx, &mut sy);
+        }
+        self.options.scaling *= Vec2::new(sx as f32, sy as f32);
+    }
+}
+
+/// Moves the markers of an image to where their source pixel is drawn, with the fractional scaling,
+/// flips and rotation of the image. A point goes to the top left corner of the block its pixel is scaled to,
+/// sizes are only scaled by whole pixels.
+struct Placement<'a, 'b> {
+    command: &'b ImageCommand<'a>,
+}
+
+impl Placement<'_, '_> {
+    /// Size of the block a source pixel is scaled to, after the quarter turns
+    fn block(&self) -> (f32, f32) {
+        let (sx, sy) = self.command.options.scaling.to_tuple();
+        if self.command.options.quarter_turns % 2 == 1 { (sy, sx) } else { (sx, sy) }
+    }
+}
+
+impl Transformer<i32, 2> for Placement<'_, '_> {
+    fn transform_vec(&self, vector: &mut Vec2<i32>) {
+        let command = self.command;
+        let u = (vector.x() - command.source.x1()) as f32 + 0.5;
+        let v = (vector.y() - command.source.y1()) as f32 + 0.5;
+
+        // Center of the pixel, moved back by half a block to its top left corner.
+        // Rotated blocks are not aligned with the canvas, their center is placed and shapes do not shift it.
+        let (x, y) = command.place(u, v);
+        let (mut x, mut y) = command.rotate(x, y, command.options.angle);
+        if self.is_axis_aligned() {
+            let (w, h) = self.block();
+            (x, y) = (x - w / 2.0, y - h / 2.0);
+        }
+
+        let (dx, dy) = command.options.destination.to_tuple();
+        *vector = Vec2::new(dx + x.round() as i32, dy + y.round() as i32);
+    }
+
+    fn scaling(&self) -> Vec2<i32> {
+        let (w, h) = self.block();
+        Vec2::new(w.round().max(1.0) as i32, h.round().max(1.0) as i32)
+    }
+
+    fn translation(&self) -> Vec2<i32> {
+        let mut origin = Vec2::new(0, 0);
+        self.transform_vec(&mut origin);
+        origin
+    }
+
+    fn is_axis_aligned(&self) -> bool {
+        self.command.options.angle == 0.0
+    }
+}
+
+/// Weighted average of colors, weighted by alpha so transparent pixels do not bleed their color
+struct Average {
+    color_space: ColorSpace,
+    sum: LinearColor,
+    weight: f32,
+}
+
+impl Average {
+    fn new(color_space: ColorSpace) -> Self {
+        Self { color_space, sum: LinearColor::default(), weight: 0.0 }
+    }
+
+    fn add(&mut self, color: Color, weight: f32) {
+        let color = self.color_space.decode(color);
+        let alpha = color.a * weight;
+
+        self.sum += LinearColor::new(color.r * alpha, color.g * alpha, color.b * alpha, alpha);
+        self.weight += weight;
+    }
+
+    fn color(&self) -> Color {
+        if self.sum.a <= 0.0 || self.weight <= 0.0 {
+            return TRANSPARENT;
+        }
+
+        let LinearColor { r, g, b, a } = self.sum;
+        self.color_space.encode(LinearColor::new(r / a, g / a, b / a, a / self.weight))
     }
 }
@@ -36,8 +36,17 @@ pub struct LineCommand {
 
 impl Transform for LineCommand {
     fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
+        if transform.is_axis_aligned() {
+            self.line.transform(transform);
+            self.options.pixel_size.transform(&Transform2D::new_scaling(transform.scaling()));
+            return;
+        }
+
+        // Rotated transforms place the centers of the pixels, so the line starts from them
+        let (sx, sy) = self.options.pixel_size.to_tuple();
+        self.line.transform(&Transform2D::new(Vec2::new(sx / 2, sy / 2), Vec2::one()));
         self.line.transform(transform);
-        self.options.pixel_size.transform(&Transform2D::new_scaling(transform.scaling()));
+        self.options.pixel_size = Vec2::zero();
     }
 }
 
@@ -71,9 +80,7 @@ impl Command for LineCommand {
         let mut y = y1;
     
         loop {
-            if let Some(pixel) = canvas.pixel_mut(x, y) {
-                *pixel = self.options.color;
-            }
+            canvas.set_pixel(x, y, self.options.color);
 
             if x == x2 && y == y2 {
                 break
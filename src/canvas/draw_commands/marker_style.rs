@@ -0,0 +1,142 @@
+use std::f32::consts::FRAC_PI_6;
+
+use crate::prelude::*;
+
+use super::{Command, DrawCommand};
+
+/// Built-in shapes for debug markers, their size stays in canvas pixels when they are transformed.
+/// They point at the center of their pixel, even once that pixel is scaled up.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MarkerStyle {
+    /// Diagonal cross centered on a point
+    Cross(Vec2),
+    /// Filled disc centered on a point
+    Dot(Vec2),
+    /// Line with a head at its end
+    Arrow(Line),
+    /// Text starting at a point
+    Label(Vec2, String),
+}
+
+impl MarkerStyle {
+    pub fn cross(x: i32, y: i32) -> Self {
+        Self::Cross(Vec2::new(x, y))
+    }
+
+    pub fn dot(x: i32, y: i32) -> Self {
+        Self::Dot(Vec2::new(x, y))
+    }
+
+    pub fn arrow(x1: i32, y1: i32, x2: i32, y2: i32) -> Self {
+        Self::Arrow(Line::new(x1, y1, x2, y2))
+    }
+
+    pub fn label(x: i32, y: i32, text: impl Into<String>) -> Self {
+        Self::Label(Vec2::new(x, y), text.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MarkerStyleOption {
+    color: Color,
+    size: i32,
+    pixel_size: Vec2,
+}
+
+impl<C: Into<Color>> From<C> for MarkerStyleOption {
+    fn from(from: C) -> Self {
+        Self {
+            color: from.into(),
+            size: 3,
+            pixel_size: Vec2::one(),
+        }
+    }
+}
+
+pub trait MarkerStyleOptionTrait: Into<MarkerStyleOption> {
+    /// Arm of the cross, radius of the dot and half of the arrow head, labels are not scaled
+    fn size(self, size: i32) -> MarkerStyleOption {
+        let mut options = self.into();
+        options.size = size.max(1);
+
+        options
+    }
+}
+
+impl<T: Into<MarkerStyleOption>> MarkerStyleOptionTrait for T {}
+
+#[derive(Debug, Clone)]
+pub struct MarkerStyleCommand {
+    style: MarkerStyle,
+    options: MarkerStyleOption,
+}
+
+impl DrawCommand for MarkerStyle {
+    type Options = MarkerStyleOption;
+    type Command = MarkerStyleCommand;
+
+    fn into_renderable(self, options: impl Into<Self::Options>) -> Self::Command {
+        MarkerStyleCommand { style: self, options: options.into() }
+    }
+}
+
+impl Command for MarkerStyleCommand {
+    fn render_canvas(&mut self, canvas: &mut dyn Canvas) {
+        let MarkerStyleOption { color, size, pixel_size } = self.options;
+        let center = |point: &Vec2| (point.x() + pixel_size.x() / 2, point.y() + pixel_size.y() / 2);
+
+        match &self.style {
+            MarkerStyle::Cross(point) => {
+                let (x, y) = center(point);
+                canvas.line(x - size, y - size, x + size, y + size, color);
+                canvas.line(x - size, y + size, x + size, y - size, color);
+            }
+            MarkerStyle::Dot(point) => {
+                let (x, y) = center(point);
+                canvas.circle(x, y, size, color.fill(color));
+            }
+            MarkerStyle::Arrow(line) => {
+                let ((x1, y1), (x2, y2)) = (center(line.start()), center(line.end()));
+                canvas.line(x1, y1, x2, y2, color);
+
+                // Two strokes going back from the tip, 30 degrees off the line
+                let angle = ((y1 - y2) as f32).atan2((x1 - x2) as f32);
+                for side in [-FRAC_PI_6, FRAC_PI_6] {
+                    let (sin, cos) = (angle + side).sin_cos();
+                    let (hx, hy) = ((cos * size as f32 * 2.0).round() as i32, (sin * size as f32 * 2.0).round() as i32);
+                    canvas.line(x2, y2, x2 + hx, y2 + hy, color);
+                }
+            }
+            MarkerStyle::Label(point, text) => {
+                let (x, y) = center(point);
+                canvas.text(x, y, text, color);
+            }
+        }
+    }
+}
+
+impl Transform for MarkerStyleCommand {
+    fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
+        // Rotated transforms place the centers of the pixels, so the style is moved to them first
+        let (sx, sy) = self.options.pixel_size.to_tuple();
+        let center = Transform2D::new(Vec2::new(sx / 2, sy / 2), Vec2::one());
+        let aligned = transform.is_axis_aligned();
+
+        let apply = |shape: &mut dyn Transform| {
+            if !aligned {
+                shape.transform(&center);
+            }
+            shape.transform(transform);
+        };
+
+        match &mut self.style {
+            MarkerStyle::Cross(point) | MarkerStyle::Dot(point) | MarkerStyle::Label(point, _) => apply(point),
+            MarkerStyle::Arrow(line) => apply(line),
+        }
+
+        match aligned {
+            true => self.options.pixel_size.transform(&Transform2D::new_scaling(transform.scaling())),
+            false => self.options.pixel_size = Vec2::zero(),
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::rc::Rc;
+
+use crate::prelude::*;
+use crate::canvas::font::{self, layout::{Align, LayoutLine, LayoutOptions, TextLayout, VerticalAlign}};
+
+use super::{Command, DrawCommand};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Text {
+    position: Vec2,
+    size: Option<Vec2>,
+    text: String,
+}
+
+impl Text {
+    pub fn new(x: i32, y: i32, text: impl Into<String>) -> Self {
+        Self { position: Vec2::new(x, y), size: None, text: text.into() }
+    }
+
+    /// Text wrapped and aligned inside of `rect`
+    pub fn new_box(rect: Rect, text: impl Into<String>) -> Self {
+        Self { position: *rect.position(), size: Some(*rect.size()), text: text.into() }
+    }
+
+    pub fn position(&self) -> &Vec2 {
+        &self.position
+    }
+
+    pub fn size(&self) -> Option<&Vec2> {
+        self.size.as_ref()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Transform for Text {
+    fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
+        self.position.transform(transform);
+
+        if let Some(size) = &mut self.size {
+            size.transform(&Transform2D::new_scaling(transform.scaling()));
+
+            // A flipped box ends at the transformed position
+            let (w, h) = size.to_tuple();
+            self.position += Vec2::new(w.min(0), h.min(0));
+            *size = Vec2::new(w.abs(), h.abs());
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TextOption {
+    color: Color,
+    scaling: Vec2,
+    font: Rc<dyn Font>,
+    layout: LayoutOptions,
+    color_space: ColorSpace,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl<C: Into<Color>> From<C> for TextOption {
+    fn from(from: C) -> Self {
+        Self {
+            color: from.into(),
+            scaling: Vec2::one(),
+            font: font::builtin(),
+            layout: LayoutOptions::default(),
+            color_space: ColorSpace::default(),
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+}
+
+pub trait TextOptionTrait: Into<TextOption> {
+    fn scaling(self, scale: i32) -> TextOption {
+        let mut options = self.into();
+        options.scaling = Vec2::new(scale, scale);
+
+        options
+    }
+
+    fn font(self, font: Rc<dyn Font>) -> TextOption {
+        let mut options = self.into();
+        options.font = font;
+
+        options
+    }
+
+    fn align(self, align: Align) -> TextOption {
+        let mut options = self.into();
+        options.layout.align = align;
+
+        options
+    }
+
+    fn vertical_align(self, vertical_align: VerticalAlign) -> TextOption {
+        let mut options = self.into();
+        options.layout.vertical_align = vertical_align;
+
+        options
+    }
+
+    fn line_spacing(self, spacing: i32) -> TextOption {
+        let mut options = self.into();
+        options.layout.line_spacing = spacing;
+
+        options
+    }
+
+    fn no_wrap(self) -> TextOption {
+        let mut options = self.into();
+        options.layout.wrap = false;
+
+        options
+    }
+
+    fn ellipsis(self) -> TextOption {
+        let mut options = self.into();
+        options.layout.ellipsis = true;
+
+        options
+    }
+
+    /// Blend the anti-aliased edges in the given color space
+    fn color_space(self, color_space: ColorSpace) -> TextOption {
+        let mut options = self.into();
+        options.color_space = color_space;
+
+        options
+    }
+
+    /// Mirrors the text left to right, inside of its box or of the space it takes
+    fn flip_horizontal(self) -> TextOption {
+        let mut options = self.into();
+        options.flip_x = !options.flip_x;
+
+        options
+    }
+
+    /// Mirrors the text top to bottom, inside of its box or of the space it takes
+    fn flip_vertical(self) -> TextOption {
+        let mut options = self.into();
+        options.flip_y = !options.flip_y;
+
+        options
+    }
+}
+
+impl<T: Into<TextOption>> TextOptionTrait for T {}
+
+#[derive(Debug, Clone)]
+pub struct TextCommand {
+    text: Text,
+    options: TextOption,
+}
+
+impl Transform for TextCommand {
+    fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
+        self.text.transform(transform);
+
+        // Negative scalings are flips, like for images
+        let (sx, sy) = transform.scaling().to_tuple();
+        self.options.scaling.transform(&Transform2D::new_scaling(Vec2::new(sx.abs(), sy.abs())));
+        self.options.flip_x ^= sx < 0;
+        self.options.flip_y ^= sy < 0;
+
+        // Text without a box ends at the transformed position when it is flipped
+        if self.text.size.is_none() && (sx < 0 || sy < 0) {
+            let (w, h) = self.layout().size().to_tuple();
+            let (w, h) = (w * self.options.scaling.x(), h * self.options.scaling.y());
+            self.text.position -= Vec2::new(if sx < 0 { w } else { 0 }, if sy < 0 { h } else { 0 });
+        }
+    }
+}
+
+impl DrawCommand for Text {
+    type Options = TextOption;
+    type Command = TextCommand;
+
+    fn into_renderable(self, options: impl Into<Self::Options>) -> Self::Command {
+        TextCommand { text: self, options: options.into() }
+    }
+}
+
+impl Command for TextCommand {
+    fn render_canvas(&mut self, canvas: &mut dyn Canvas) {
+        let (sx, sy) = self.options.scaling.to_tuple();
+        if sx <= 0 || sy <= 0 {
+            return;
+        }
+
+        let layout = self.layout();
+
+        // Flipped text is mirrored inside of the space it takes
+        let (w, h) = self.text.size.unwrap_or(Vec2::new(layout.size().x() * sx, layout.size().y() * sy)).to_tuple();
+        let (x, y) = self.text.position.to_tuple();
+        let origin = Vec2::new(if self.options.flip_x { x + w } else { x }, if self.options.flip_y { y + h } else { y });
+
+        for line in layout.lines() {
+            self.render_line(canvas, line, origin, line.position());
+        }
+    }
+}
+
+impl TextCommand {
+    /// The layout is done in font pixels, which are then scaled
+    fn layout(&self) -> TextLayout {
+        let (sx, sy) = self.options.scaling.to_tuple();
+        let size = self.text.size.map(|size| Vec2::new(size.x() / sx.max(1), size.y() / sy.max(1)));
+
+        TextLayout::new(&*self.options.font, &self.text.text, size, &self.options.layout)
+    }
+
+    /// Top left canvas pixel of the block the font pixel `x, y` is scaled to
+    fn block(&self, origin: Vec2, x: i32, y: i32) -> (i32, i32) {
+        let (sx, sy) = self.options.scaling.to_tuple();
+        let (ox, oy) = origin.to_tuple();
+
+        let px = if self.options.flip_x { ox - (x + 1) * sx } else { ox + x * sx };
+        let py = if self.options.flip_y { oy - (y + 1) * sy } else { oy + y * sy };
+        (px, py)
+    }
+
+    /// `position` is where the line starts in font pixels
+    fn render_line(&self, canvas: &mut dyn Canvas, line: &LayoutLine, origin: Vec2, position: Vec2) {
+        let font = &self.options.font;
+        let color = self.options.color;
+        let color_space = self.options.color_space;
+        let (sx, sy) = self.options.scaling.to_tuple();
+        let (mut x, y) = position.to_tuple();
+
+        let mut previous = None;
+        let mut spaces = 0;
+
+        for c in line.text().chars() {
+            if let Some(previous) = previous {
+                x += font.kerning(previous, c);
+            }
+            previous = Some(c);
+
+            if c == ' ' {
+                x += line.space_extra(spaces);
+                spaces += 1;
+            }
+
+            let Some(glyph) = font.glyph(c) else { continue };
+            let (ox, oy) = glyph.offset().to_tuple();
+            let (w, h) = glyph.image().size_i32();
+
+            for j in 0..h {
+                for i in 0..w {
+                    let Some(coverage) = glyph.image().pixel(i, j) else { continue };
+                    if coverage.is_transparent() {
+                        continue;
+                    }
+
+                    // Anti-aliased edges are blended with what is already there
+                    let alpha = coverage.a();
+
+                    // Each pixel of the glyph becomes a sx * sy block
+                    let (px, py) = self.block(origin, x + ox + i, y + oy + j);
+                    for dy in 0..sy {
+                        for dx in 0..sx {
+                            let Some(pixel) = canvas.pixel(px + dx, py + dy) else { continue };
+                            canvas.set_pixel(px + dx, py + dy, if alpha == 255 { color } else { color_space.mix(pixel, color, alpha) });
+                        }
+                    }
+                }
+            }
+
+            x += glyph.advance();
+        }
+    }
+}
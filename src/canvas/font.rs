@@ -0,0 +1,135 @@
+use std::fmt::{Debug, Display};
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+pub mod bitmap;
+pub mod truetype;
+pub mod layout;
+mod bdf;
+mod bmfont;
+mod psf;
+mod builtin;
+
+use bitmap::BitmapFont;
+
+pub use bmfont::PageColors;
+
+pub trait Font: Debug {
+    /// Distance between the tops of two consecutive lines
+    fn line_height(&self) -> i32;
+
+    /// Distance between the top of the line and the baseline
+    fn ascent(&self) -> i32;
+
+    fn glyph(&self, c: char) -> Option<Glyph>;
+
+    /// Adjustment of the advance between two consecutive characters
+    fn kerning(&self, _left: char, _right: char) -> i32 {
+        0
+    }
+
+    /// Width of a single line of text, including kerning
+    fn line_width(&self, line: &str) -> i32 {
+        let mut width = 0;
+        let mut previous = None;
+
+        for c in line.chars() {
+            if let Some(previous) = previous {
+                width += self.kerning(previous, c);
+            }
+
+            width += self.glyph(c).map_or(0, |glyph| glyph.advance());
+            previous = Some(c);
+        }
+
+        width
+    }
+
+    /// Size of the box enclosing `text`, lines are separated by `\n`
+    fn measure(&self, text: &str) -> Vec2 {
+        let mut width = 0;
+        let mut lines = 0;
+
+        for line in text.split('\n') {
+            width = width.max(self.line_width(line));
+            lines += 1;
+        }
+
+        Vec2::new(width, lines * self.line_height())
+    }
+}
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(std::io::Error),
+    Invalid(String),
+}
+
+impl Display for FontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontError::Io(error) => write!(f, "could not read font: {error}"),
+            FontError::Invalid(reason) => write!(f, "invalid font: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+impl From<std::io::Error> for FontError {
+    fn from(error: std::io::Error) -> Self {
+        FontError::Io(error)
+    }
+}
+
+impl From<ImageError> for FontError {
+    fn from(error: ImageError) -> Self {
+        match error {
+            ImageError::Io(error) => FontError::Io(error),
+            error => FontError::Invalid(error.to_string()),
+        }
+    }
+}
+
+/// A single rasterized character, the alpha channel of the image is used as coverage
+#[derive(Debug, Clone)]
+pub struct Glyph {
+    image: Rc<CanvasImpl>,
+    offset: Vec2,
+    advance: i32,
+}
+
+impl Glyph {
+    pub fn new(image: Rc<CanvasImpl>, offset: Vec2, advance: i32) -> Self {
+        Self { image, offset, advance }
+    }
+
+    pub fn image(&self) -> &CanvasImpl {
+        &self.image
+    }
+
+    /// Position of the top left corner of the image relative to the top of the line
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+
+    /// Horizontal distance to the next character
+    pub fn advance(&self) -> i32 {
+        self.advance
+    }
+}
+
+thread_local! {
+    static BUILTIN: Rc<BitmapFont> = {
+        let mut font = BitmapFont::from_bits(builtin::WIDTH, builtin::HEIGHT, builtin::ASCENT, builtin::FIRST, &builtin::GLYPHS);
+        font.set_fallback(Some('?'));
+
+        Rc::new(font)
+    };
+}
+
+/// The embedded 6x10 monospace font, used when no other font is specified
+pub fn builtin() -> Rc<dyn Font> {
+    BUILTIN.with(|font| font.clone())
+}
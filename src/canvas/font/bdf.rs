@@ -0,0 +1,100 @@
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+use super::bitmap::{bits_image, BitmapFont};
+
+/// Glyph being parsed between STARTCHAR and ENDCHAR
+#[derive(Default)]
+struct BdfChar {
+    encoding: Option<char>,
+    advance: i32,
+    bbx: [i32; 4],
+    bitmap: Option<Vec<u8>>,
+}
+
+impl BitmapFont {
+    pub fn from_bdf_path(path: &str) -> Result<Self, FontError> {
+        Self::from_bdf(&std::fs::read_to_string(path)?)
+    }
+
+    /// Glyph Bitmap Distribution Format (.bdf)
+    pub fn from_bdf(source: &str) -> Result<Self, FontError> {
+        let mut bounding_box = None;
+        let mut ascent = None;
+        let mut descent = None;
+        let mut default_char = None;
+
+        let mut chars = Vec::new();
+        let mut current: Option<BdfChar> = None;
+
+        for line in source.lines() {
+            let Some(keyword) = line.split_whitespace().next() else { continue };
+            let numbers = || line.split_whitespace().skip(1).map(|word| word.parse::<i32>().ok()).collect::<Option<Vec<_>>>();
+
+            // Hex rows of the glyph bitmap
+            if let Some(BdfChar { bitmap: Some(bitmap), .. }) = &mut current {
+                if keyword != "ENDCHAR" {
+                    for i in (0..keyword.len()).step_by(2) {
+                        let byte = keyword.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok());
+                        bitmap.push(byte.ok_or_else(|| invalid(line))?);
+                    }
+
+                    continue;
+                }
+            }
+
+            match keyword {
+                "FONTBOUNDINGBOX" => bounding_box = Some(four(numbers(), line)?),
+                "FONT_ASCENT" => ascent = numbers().and_then(|n| n.first().copied()),
+                "FONT_DESCENT" => descent = numbers().and_then(|n| n.first().copied()),
+                "DEFAULT_CHAR" => default_char = numbers().and_then(|n| char::from_u32(*n.first()? as u32)),
+                "STARTCHAR" => current = Some(BdfChar::default()),
+                "ENCODING" => if let Some(current) = &mut current {
+                    // Negative encodings are glyphs without a code point
+                    current.encoding = numbers().and_then(|n| char::from_u32(u32::try_from(*n.first()?).ok()?));
+                },
+                "DWIDTH" => if let Some(current) = &mut current {
+                    current.advance = numbers().and_then(|n| n.first().copied()).ok_or_else(|| invalid(line))?;
+                },
+                "BBX" => if let Some(current) = &mut current {
+                    current.bbx = four(numbers(), line)?;
+                },
+                "BITMAP" => if let Some(current) = &mut current {
+                    current.bitmap = Some(Vec::new());
+                },
+                "ENDCHAR" => chars.extend(current.take()),
+                _ => {}
+            }
+        }
+
+        let [_, height, _, y_offset] = bounding_box.ok_or_else(|| FontError::Invalid("missing FONTBOUNDINGBOX".into()))?;
+        let ascent = ascent.unwrap_or(height + y_offset);
+        let descent = descent.unwrap_or(-y_offset);
+
+        let mut font = BitmapFont::new(ascent + descent, ascent);
+
+        for glyph in chars {
+            let Some(c) = glyph.encoding else { continue };
+            let [width, height, x, y] = glyph.bbx;
+            let (width, height) = (width.max(0) as usize, height.max(0) as usize);
+
+            let image = bits_image(width, height, width.div_ceil(8), &glyph.bitmap.unwrap_or_default());
+
+            // BBX offsets are relative to the baseline, with y pointing up
+            let offset = Vec2::new(x, ascent - y - height as i32);
+            font.insert(c, Glyph::new(Rc::new(image), offset, glyph.advance));
+        }
+
+        font.set_fallback(default_char);
+        Ok(font)
+    }
+}
+
+fn four(numbers: Option<Vec<i32>>, line: &str) -> Result<[i32; 4], FontError> {
+    numbers.and_then(|numbers| numbers.try_into().ok()).ok_or_else(|| invalid(line))
+}
+
+fn invalid(line: &str) -> FontError {
+    FontError::Invalid(format!("unexpected BDF line `{line}`"))
+}
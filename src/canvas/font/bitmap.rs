@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+use super::{Font, Glyph};
+
+#[derive(Debug, Clone)]
+pub struct BitmapFont {
+    glyphs: HashMap<char, Glyph>,
+    kernings: HashMap<(char, char), i32>,
+    fallback: Option<char>,
+
+    line_height: i32,
+    ascent: i32,
+}
+
+impl BitmapFont {
+    pub fn new(line_height: i32, ascent: i32) -> Self {
+        Self {
+            glyphs: HashMap::new(),
+            kernings: HashMap::new(),
+            fallback: None,
+            line_height,
+            ascent,
+        }
+    }
+
+    /// Monospace font from 1 bit per pixel rows, the leftmost pixel is the most significant bit.
+    /// The glyphs are consecutive characters starting at `first`.
+    pub fn from_bits<const H: usize>(width: usize, height: usize, ascent: i32, first: char, glyphs: &[[u8; H]]) -> Self {
+        assert!(width <= 8 && height <= H);
+
+        let mut font = Self::new(height as i32, ascent);
+
+        for (i, rows) in glyphs.iter().enumerate() {
+            let Some(c) = char::from_u32(first as u32 + i as u32) else { continue };
+            let image = bits_image(width, height, 1, rows);
+
+            font.insert(c, Glyph::new(Rc::new(image), Vec2::zero(), width as i32));
+        }
+
+        font
+    }
+
+    pub fn insert(&mut self, c: char, glyph: Glyph) {
+        self.glyphs.insert(c, glyph);
+    }
+
+    pub fn insert_kerning(&mut self, left: char, right: char, amount: i32) {
+        self.kernings.insert((left, right), amount);
+    }
+
+    /// Character drawn in place of the ones missing from the font
+    pub fn set_fallback(&mut self, fallback: Option<char>) {
+        self.fallback = fallback;
+    }
+}
+
+impl Font for BitmapFont {
+    fn line_height(&self) -> i32 {
+        self.line_height
+    }
+
+    fn ascent(&self) -> i32 {
+        self.ascent
+    }
+
+    fn glyph(&self, c: char) -> Option<Glyph> {
+        self.glyphs.get(&c)
+            .or_else(|| self.glyphs.get(&self.fallback?))
+            .cloned()
+    }
+
+    fn kerning(&self, left: char, right: char) -> i32 {
+        self.kernings.get(&(left, right)).copied().unwrap_or(0)
+    }
+}
+
+/// Image of 1 bit per pixel rows of `stride` bytes, the leftmost pixel is the most significant bit.
+/// Empty glyphs, like a space with a zero width bounding box, have no rows.
+pub(crate) fn bits_image(width: usize, height: usize, stride: usize, data: &[u8]) -> CanvasImpl {
+    let mut image = CanvasImpl::new(width, height);
+    if stride == 0 {
+        return image;
+    }
+
+    for (y, row) in data.chunks(stride).take(height).enumerate() {
+        for x in 0..width {
+            if row.get(x / 8).is_some_and(|byte| byte & (0x80 >> (x % 8)) != 0) {
+                image.pixel_mut(x as i32, y as i32).color(WHITE);
+            }
+        }
+    }
+
+    image
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+use super::bitmap::BitmapFont;
+
+/// How the colors of BMFont pages are turned into glyphs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PageColors {
+    /// The pixels are used as they are, colored and outlined glyphs keep their colors
+    #[default]
+    Keep,
+    /// Pages exported without alpha store the coverage as brightness, the glyphs are white
+    Brightness,
+}
+
+impl BitmapFont {
+    /// AngelCode BMFont text descriptor (.fnt), the page images are loaded relative to it
+    pub fn from_bmfont_path(path: &str, colors: PageColors) -> Result<Self, FontError> {
+        let source = std::fs::read_to_string(path)?;
+        let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+
+        let mut font = None;
+        let mut pages = HashMap::new();
+
+        for line in source.lines() {
+            let mut words = split_words(line);
+            let Some(tag) = words.next() else { continue };
+            let attributes: HashMap<&str, &str> = words.filter_map(|word| word.split_once('=')).collect();
+
+            let number = |key: &str| {
+                let value = attributes.get(key).ok_or_else(|| FontError::Invalid(format!("missing `{key}` in `{line}`")))?;
+                value.parse::<i32>().map_err(|_| FontError::Invalid(format!("`{key}` is not a number in `{line}`")))
+            };
+
+            match tag {
+                "common" => font = Some(BitmapFont::new(number("lineHeight")?, number("base")?)),
+                "page" => {
+                    let file = attributes.get("file").ok_or_else(|| FontError::Invalid(format!("missing `file` in `{line}`")))?;
+                    let file = directory.join(file.trim_matches('"'));
+                    let file = file.to_str().ok_or_else(|| FontError::Invalid(format!("invalid page path `{}`", file.display())))?;
+
+                    let mut page = CanvasImpl::from_image_path(file)?;
+                    if colors == PageColors::Brightness {
+                        brightness_to_alpha(&mut page);
+                    }
+
+                    pages.insert(number("id")?, page);
+                }
+                "char" => {
+                    let font = font.as_mut().ok_or_else(|| FontError::Invalid("`char` before `common`".into()))?;
+                    let Some(c) = char::from_u32(number("id")? as u32) else { continue };
+                    let page = pages.get(&number("page")?).ok_or_else(|| FontError::Invalid(format!("missing page in `{line}`")))?;
+
+                    let (x, y, width, height) = (number("x")?, number("y")?, number("width")?, number("height")?);
+                    let mut image = CanvasImpl::new(width.max(0) as usize, height.max(0) as usize);
+                    for j in 0..height {
+                        for i in 0..width {
+                            let Some(color) = page.pixel(x + i, y + j) else { continue };
+                            image.pixel_mut(i, j).color(color);
+                        }
+                    }
+
+                    let offset = Vec2::new(number("xoffset")?, number("yoffset")?);
+                    font.insert(c, Glyph::new(Rc::new(image), offset, number("xadvance")?));
+                }
+                "kerning" => {
+                    let font = font.as_mut().ok_or_else(|| FontError::Invalid("`kerning` before `common`".into()))?;
+                    let (Some(left), Some(right)) = (char::from_u32(number("first")? as u32), char::from_u32(number("second")? as u32)) else { continue };
+
+                    font.insert_kerning(left, right, number("amount")?);
+                }
+                _ => {}
+            }
+        }
+
+        font.ok_or_else(|| FontError::Invalid("missing `common` line".into()))
+    }
+}
+
+/// Words separated by spaces, quoted values can contain spaces
+fn split_words(line: &str) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+
+    line.split(move |c: char| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+
+        c.is_whitespace() && !quoted
+    }).filter(|word| !word.is_empty())
+}
+
+fn brightness_to_alpha(page: &mut CanvasImpl) {
+    for color in page.buffer_mut() {
+        let alpha = color.r().max(color.g()).max(color.b()) as u32;
+        *color = Color::from(alpha << 24 | 0xFF_FF_FF);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::prelude::*;
+
+const ELLIPSIS: &str = "...";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Spreads the words over the whole width, except for the last line of a paragraph
+    Justify,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayoutOptions {
+    pub align: Align,
+    pub vertical_align: VerticalAlign,
+    /// Extra pixels between two lines
+    pub line_spacing: i32,
+    /// Break lines that are wider than the box
+    pub wrap: bool,
+    /// Shorten the text with "..." if it does not fit in the box
+    pub ellipsis: bool,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            align: Align::default(),
+            vertical_align: VerticalAlign::default(),
+            line_spacing: 0,
+            wrap: true,
+            ellipsis: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LayoutLine {
+    text: String,
+    position: Vec2,
+    width: i32,
+    justify: i32,
+}
+
+impl LayoutLine {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Top left corner of the line relative to the box
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    /// Width including the space added by justification
+    pub fn width(&self) -> i32 {
+        self.width + self.justify
+    }
+
+    /// Pixels added to the spaces of the line, the first ones get the remainder
+    pub fn space_extra(&self, index: i32) -> i32 {
+        let spaces = self.text.matches(' ').count() as i32;
+        if spaces == 0 {
+            return 0;
+        }
+
+        self.justify / spaces + (index < self.justify % spaces) as i32
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextLayout {
+    lines: Vec<LayoutLine>,
+    line_height: i32,
+    size: Vec2,
+}
+
+impl TextLayout {
+    /// Lays out `text` inside a box of `size`, or around the origin if there is no box
+    pub fn new(font: &dyn Font, text: &str, size: Option<Vec2>, options: &LayoutOptions) -> Self {
+        let max_width = size.map(|size| *size.x()).filter(|_| options.wrap);
+        // Lines can overlap with a negative spacing, but they always move down
+        let advance = (font.line_height() + options.line_spacing).max(1);
+
+        // (text, width, is the last line of its paragraph)
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            match max_width {
+                Some(max_width) => wrap(font, paragraph, max_width, &mut lines),
+                None => lines.push((paragraph.to_string(), font.line_width(paragraph), true)),
+            }
+        }
+
+        if let (Some(size), true) = (size, options.ellipsis) {
+            let max_lines = ((*size.y() + options.line_spacing) / advance).max(0) as usize;
+            if max_lines == 0 {
+                return Self { lines: Vec::new(), line_height: font.line_height(), size: Vec2::zero() };
+            }
+
+            if lines.len() > max_lines {
+                lines.truncate(max_lines);
+                let last = lines.last_mut().unwrap();
+                last.0.push_str(ELLIPSIS);
+            }
+
+            for (line, width, _) in lines.iter_mut() {
+                if font.line_width(line) > *size.x() {
+                    *line = shorten(font, line, *size.x());
+                }
+                *width = font.line_width(line);
+            }
+        }
+
+        let width = size.map_or_else(|| lines.iter().map(|line| line.1).max().unwrap_or(0), |size| *size.x());
+        let height = lines.len() as i32 * advance - options.line_spacing;
+
+        let top = match (size, options.vertical_align) {
+            (None, _) | (_, VerticalAlign::Top) => 0,
+            (Some(size), VerticalAlign::Middle) => (*size.y() - height) / 2,
+            (Some(size), VerticalAlign::Bottom) => *size.y() - height,
+        };
+
+        let lines: Vec<_> = lines.into_iter().enumerate().map(|(i, (text, line_width, last))| {
+            let free = width - line_width;
+
+            let (x, justify) = match options.align {
+                Align::Left => (0, 0),
+                Align::Center => (free / 2, 0),
+                Align::Right => (free, 0),
+                Align::Justify if last || !text.contains(' ') => (0, 0),
+                Align::Justify => (0, free.max(0)),
+            };
+
+            LayoutLine {
+                text,
+                position: Vec2::new(x, top + i as i32 * advance),
+                width: line_width,
+                justify,
+            }
+        }).collect();
+
+        let size = Vec2::new(lines.iter().map(|line| line.width()).max().unwrap_or(0), height.max(0));
+
+        Self { lines, line_height: font.line_height(), size }
+    }
+
+    pub fn lines(&self) -> &[LayoutLine] {
+        &self.lines
+    }
+
+    pub fn line_height(&self) -> i32 {
+        self.line_height
+    }
+
+    /// Size of the text, without the empty space of the box
+    pub fn size(&self) -> Vec2 {
+        self.size
+    }
+}
+
+/// Greedy word wrapping, words longer than a line are broken between characters
+fn wrap(font: &dyn Font, paragraph: &str, max_width: i32, lines: &mut Vec<(String, i32, bool)>) {
+    let mut line = String::new();
+
+    for word in paragraph.split(' ') {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{line} {word}") };
+
+        if font.line_width(&candidate) <= max_width {
+            line = candidate;
+            continue;
+        }
+
+        if !line.is_empty() {
+            let width = font.line_width(&line);
+            lines.push((std::mem::take(&mut line), width, false));
+        }
+
+        for c in word.chars() {
+            line.push(c);
+
+            if line.chars().count() > 1 && font.line_width(&line) > max_width {
+                line.pop();
+                let width = font.line_width(&line);
+                lines.push((std::mem::replace(&mut line, c.to_string()), width, false));
+            }
+        }
+    }
+
+    let width = font.line_width(&line);
+    lines.push((line, width, true));
+}
+
+/// Removes characters until the line with the ellipsis fits
+fn shorten(font: &dyn Font, line: &str, max_width: i32) -> String {
+    let mut text = line.strip_suffix(ELLIPSIS).unwrap_or(line).to_string();
+
+    while !text.is_empty() && font.line_width(&format!("{text}{ELLIPSIS}")) > max_width {
+        text.pop();
+    }
+
+    text.push_str(ELLIPSIS);
+    text
+}
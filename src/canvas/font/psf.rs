@@ -0,0 +1,119 @@
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+use super::bitmap::{bits_image, BitmapFont};
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE_512: u8 = 0x01;
+const PSF1_MODE_UNICODE: u8 = 0x02;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+const PSF2_HAS_UNICODE_TABLE: usize = 0x01;
+
+impl BitmapFont {
+    pub fn from_psf_path(path: &str) -> Result<Self, FontError> {
+        Self::from_psf(&std::fs::read(path)?)
+    }
+
+    /// Linux console PC Screen Font, version 1 or 2 (.psf, .psfu)
+    pub fn from_psf(bytes: &[u8]) -> Result<Self, FontError> {
+        if bytes.starts_with(&PSF1_MAGIC) {
+            let (mode, height) = (*bytes.get(2).ok_or_else(truncated)?, *bytes.get(3).ok_or_else(truncated)? as usize);
+            let count = if mode & PSF1_MODE_512 != 0 { 512 } else { 256 };
+            if height == 0 {
+                return Err(empty());
+            }
+
+            let glyphs = bytes.get(4..4 + count * height).ok_or_else(truncated)?;
+            let table = (mode & PSF1_MODE_UNICODE != 0).then(|| psf1_table(&bytes[4 + count * height..], count));
+
+            Ok(build(8, height, glyphs, table))
+        } else if bytes.starts_with(&PSF2_MAGIC) {
+            let header = |i: usize| bytes.get(i * 4..i * 4 + 4).map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize).ok_or_else(truncated);
+            let (header_size, flags, count, glyph_size, height, width) = (header(2)?, header(3)?, header(4)?, header(5)?, header(6)?, header(7)?);
+
+            if width == 0 || height == 0 {
+                return Err(empty());
+            }
+            if glyph_size != width.div_ceil(8) * height {
+                return Err(FontError::Invalid("PSF2 glyph size does not match its dimensions".into()));
+            }
+
+            let end = header_size + count * glyph_size;
+            let glyphs = bytes.get(header_size..end).ok_or_else(truncated)?;
+            let table = (flags & PSF2_HAS_UNICODE_TABLE != 0).then(|| psf2_table(&bytes[end..], count));
+
+            Ok(build(width, height, glyphs, table))
+        } else {
+            Err(FontError::Invalid("not a PSF font".into()))
+        }
+    }
+}
+
+/// Without a unicode table the glyph index is the code point
+fn build(width: usize, height: usize, glyphs: &[u8], table: Option<Vec<Vec<char>>>) -> BitmapFont {
+    // PSF has no baseline, everything is above it
+    let mut font = BitmapFont::new(height as i32, height as i32);
+    let size = width.div_ceil(8) * height;
+
+    for (i, data) in glyphs.chunks_exact(size).enumerate() {
+        let glyph = Glyph::new(Rc::new(bits_image(width, height, width.div_ceil(8), data)), Vec2::zero(), width as i32);
+
+        match &table {
+            Some(table) => for &c in table.get(i).into_iter().flatten() {
+                font.insert(c, glyph.clone());
+            },
+            None => if let Some(c) = char::from_u32(i as u32) {
+                font.insert(c, glyph);
+            },
+        }
+    }
+
+    font.set_fallback(Some('?'));
+    font
+}
+
+/// UCS-2 code points for each glyph, 0xFFFF ends a glyph and 0xFFFE starts the combining sequences
+fn psf1_table(bytes: &[u8], count: usize) -> Vec<Vec<char>> {
+    let mut table = vec![Vec::new(); count];
+    let mut values = bytes.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+
+    for chars in table.iter_mut() {
+        let mut sequence = false;
+
+        for value in values.by_ref() {
+            match value {
+                0xFFFF => break,
+                0xFFFE => sequence = true,
+                _ if sequence => {}
+                _ => chars.extend(char::from_u32(value as u32)),
+            }
+        }
+    }
+
+    table
+}
+
+/// UTF-8 code points for each glyph, 0xFF ends a glyph and 0xFE starts the combining sequences
+fn psf2_table(bytes: &[u8], count: usize) -> Vec<Vec<char>> {
+    let mut table = vec![Vec::new(); count];
+    let mut entries = bytes.split(|&b| b == 0xFF);
+
+    for chars in table.iter_mut() {
+        let Some(entry) = entries.next() else { break };
+        let single = entry.split(|&b| b == 0xFE).next().unwrap_or_default();
+
+        chars.extend(String::from_utf8_lossy(single).chars().filter(|&c| c != char::REPLACEMENT_CHARACTER));
+    }
+
+    table
+}
+
+fn truncated() -> FontError {
+    FontError::Invalid("truncated PSF font".into())
+}
+
+fn empty() -> FontError {
+    FontError::Invalid("PSF glyphs have a zero width or height".into())
+}
@@ -0,0 +1,86 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use ab_glyph::{Font as _, FontVec, PxScale, ScaleFont};
+
+use crate::prelude::*;
+
+use super::{Font, FontError, Glyph};
+
+/// TrueType or OpenType font rasterized at a fixed pixel size
+#[derive(Debug)]
+pub struct TrueTypeFont {
+    font: FontVec,
+    scale: PxScale,
+
+    // Glyphs are rasterized the first time they are drawn
+    cache: RefCell<HashMap<char, Glyph>>,
+}
+
+impl TrueTypeFont {
+    pub fn from_path(path: &str, size: f32) -> Result<Self, FontError> {
+        Self::from_bytes(std::fs::read(path)?, size)
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>, size: f32) -> Result<Self, FontError> {
+        let font = FontVec::try_from_vec(bytes).map_err(|_| FontError::Invalid("not a TrueType or OpenType font".into()))?;
+
+        Ok(Self {
+            font,
+            scale: PxScale::from(size),
+            cache: RefCell::new(HashMap::new()),
+        })
+    }
+
+    pub fn size(&self) -> f32 {
+        self.scale.y
+    }
+
+    fn rasterize(&self, c: char) -> Glyph {
+        let font = self.font.as_scaled(self.scale);
+        let glyph = font.scaled_glyph(c);
+        let advance = font.h_advance(glyph.id).round() as i32;
+
+        let Some(outline) = self.font.outline_glyph(glyph) else {
+            // Whitespace has no outline, only an advance
+            return Glyph::new(Rc::new(CanvasImpl::new(0, 0)), Vec2::zero(), advance);
+        };
+
+        let bounds = outline.px_bounds();
+        let mut image = CanvasImpl::new(bounds.width() as usize, bounds.height() as usize);
+
+        outline.draw(|x, y, coverage| {
+            let alpha = (coverage.clamp(0.0, 1.0) * 255.0).round() as u32;
+            image.pixel_mut(x as i32, y as i32).color(Color::from(alpha << 24 | 0xFF_FF_FF));
+        });
+
+        let offset = Vec2::new(bounds.min.x as i32, (bounds.min.y + font.ascent()) as i32);
+        Glyph::new(Rc::new(image), offset, advance)
+    }
+}
+
+impl Font for TrueTypeFont {
+    fn line_height(&self) -> i32 {
+        let font = self.font.as_scaled(self.scale);
+        (font.height() + font.line_gap()).round() as i32
+    }
+
+    fn ascent(&self) -> i32 {
+        self.font.as_scaled(self.scale).ascent().round() as i32
+    }
+
+    fn glyph(&self, c: char) -> Option<Glyph> {
+        let glyph = self.cache.borrow_mut()
+            .entry(c)
+            .or_insert_with(|| self.rasterize(c))
+            .clone();
+
+        Some(glyph)
+    }
+
+    fn kerning(&self, left: char, right: char) -> i32 {
+        let font = self.font.as_scaled(self.scale);
+        font.kern(font.glyph_id(left), font.glyph_id(right)).round() as i32
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt::Display;
+use std::io::{BufRead, Seek, Write};
+
+use crate::prelude::*;
+
+/// File format used when exporting a canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    Bmp,
+    Tga,
+    Qoi,
+    Tiff,
+    /// Lossless WebP
+    WebP,
+    Ico,
+    Gif,
+    /// Lossy and without alpha
+    Jpeg,
+}
+
+impl ImageFormat {
+    /// Format matching the extension of `path`
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = std::path::Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+
+        Some(match extension.as_str() {
+            "png" => ImageFormat::Png,
+            "bmp" => ImageFormat::Bmp,
+            "tga" => ImageFormat::Tga,
+            "qoi" => ImageFormat::Qoi,
+            "tif" | "tiff" => ImageFormat::Tiff,
+            "webp" => ImageFormat::WebP,
+            "ico" => ImageFormat::Ico,
+            "gif" => ImageFormat::Gif,
+            "jpg" | "jpeg" => ImageFormat::Jpeg,
+            _ => return None,
+        })
+    }
+
+    fn to_image(self) -> image::ImageFormat {
+        match self {
+            ImageFormat::Png => image::ImageFormat::Png,
+            ImageFormat::Bmp => image::ImageFormat::Bmp,
+            ImageFormat::Tga => image::ImageFormat::Tga,
+            ImageFormat::Qoi => image::ImageFormat::Qoi,
+            ImageFormat::Tiff => image::ImageFormat::Tiff,
+            ImageFormat::WebP => image::ImageFormat::WebP,
+            ImageFormat::Ico => image::ImageFormat::Ico,
+            ImageFormat::Gif => image::ImageFormat::Gif,
+            ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(std::io::Error),
+    /// Unknown format, corrupted data or failed encoding
+    Codec(image::ImageError),
+    /// The format can not be guessed from the file extension
+    UnknownFormat(String),
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImageError::Io(error) => write!(f, "could not access image: {error}"),
+            ImageError::Codec(error) => write!(f, "invalid image: {error}"),
+            ImageError::UnknownFormat(path) => write!(f, "unknown image format for `{path}`"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl From<std::io::Error> for ImageError {
+    fn from(error: std::io::Error) -> Self {
+        ImageError::Io(error)
+    }
+}
+
+impl From<image::ImageError> for ImageError {
+    fn from(error: image::ImageError) -> Self {
+        match error {
+            image::ImageError::IoError(error) => ImageError::Io(error),
+            error => ImageError::Codec(error),
+        }
+    }
+}
+
+impl CanvasImpl {
+    /// Loads an image file, the format is guessed from its content or else its extension
+    pub fn from_image_path(path: &str) -> Result<Self, ImageError> {
+        Ok(image::ImageReader::open(path)?.with_guessed_format()?.decode()?.into())
+    }
+
+    /// Loads an encoded image, like the content of a PNG file
+    pub fn from_image_bytes(bytes: &[u8]) -> Result<Self, ImageError> {
+        Self::from_image_reader(std::io::Cursor::new(bytes))
+    }
+
+    pub fn from_image_reader(reader: impl BufRead + Seek) -> Result<Self, ImageError> {
+        Ok(image::ImageReader::new(reader).with_guessed_format()?.decode()?.into())
+    }
+
+    /// Canvas out of RGBA bytes, `None` if there are not `width * height` pixels
+    pub fn from_rgba_bytes(width: usize, height: usize, bytes: &[u8]) -> Option<Self> {
+        (bytes.len() == width * height * 4).then(|| Self::new_buffer(width, height, Color::from_rgba_bytes(bytes)))
+    }
+
+    pub fn from_bgra_bytes(width: usize, height: usize, bytes: &[u8]) -> Option<Self> {
+        (bytes.len() == width * height * 4).then(|| Self::new_buffer(width, height, Color::from_bgra_bytes(bytes)))
+    }
+}
+
+impl From<image::RgbaImage> for CanvasImpl {
+    fn from(image: image::RgbaImage) -> Self {
+        let (width, height) = image.dimensions();
+        Self::new_buffer(width as usize, height as usize, Color::from_rgba_bytes(image.as_raw()))
+    }
+}
+
+/// Other pixel formats are converted to 8 bit RGBA
+impl From<image::DynamicImage> for CanvasImpl {
+    fn from(image: image::DynamicImage) -> Self {
+        image.into_rgba8().into()
+    }
+}
+
+impl From<&CanvasImpl> for image::RgbaImage {
+    fn from(canvas: &CanvasImpl) -> Self {
+        to_rgba8(canvas)
+    }
+}
+
+impl From<&CanvasImpl> for image::DynamicImage {
+    fn from(canvas: &CanvasImpl) -> Self {
+        image::DynamicImage::ImageRgba8(to_rgba8(canvas))
+    }
+}
+
+/// Pixels of a canvas as RGBA bytes, alpha included
+pub(crate) fn to_rgba8<C: Canvas + ?Sized>(canvas: &C) -> image::RgbaImage {
+    let (width, height) = canvas.size();
+    image::RgbaImage::from_raw(width as u32, height as u32, canvas.to_buffer().to_rgba_bytes()).unwrap()
+}
+
+pub(crate) fn write_image<C: Canvas + ?Sized>(canvas: &C, mut writer: impl Write + Seek, format: ImageFormat) -> Result<(), ImageError> {
+    let image = to_rgba8(canvas);
+
+    match format {
+        ImageFormat::Jpeg => image::DynamicImage::ImageRgba8(image).to_rgb8().write_to(&mut writer, format.to_image())?,
+        _ => image.write_to(&mut writer, format.to_image())?,
+    }
+
+    writer.flush()?;
+    Ok(())
+}
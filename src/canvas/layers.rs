@@ -0,0 +1,198 @@
+use crate::prelude::*;
+
+/// How the colors of a layer are combined with the layers below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    /// Darkens, white is neutral, for shadows
+    Multiply,
+    /// Lightens, black is neutral, for glows
+    Screen,
+    /// Multiply on dark and screen on light parts of the layers below, for contrast
+    Overlay,
+    Darken,
+    Lighten,
+    /// Sum of the colors, for light and fire effects
+    Add,
+    Difference,
+}
+
+impl BlendMode {
+    fn channel(self, source: f32, destination: f32) -> f32 {
+        match self {
+            BlendMode::Normal => source,
+            BlendMode::Multiply => source * destination,
+            BlendMode::Screen => source + destination - source * destination,
+            BlendMode::Overlay if destination <= 0.5 => 2.0 * source * destination,
+            BlendMode::Overlay => 1.0 - 2.0 * (1.0 - source) * (1.0 - destination),
+            BlendMode::Darken => source.min(destination),
+            BlendMode::Lighten => source.max(destination),
+            BlendMode::Add => (source + destination).min(1.0),
+            BlendMode::Difference => (source - destination).abs(),
+        }
+    }
+
+    /// Draws `source` over `destination`, its alpha multiplied by `opacity`
+    pub fn blend(self, source: Color, destination: Color, opacity: f32) -> Color {
+        let space = ColorSpace::Srgb;
+        let mut s = space.decode(source);
+        s.a *= opacity.clamp(0.0, 1.0);
+
+        if s.a <= 0.0 {
+            return destination;
+        }
+        if self == BlendMode::Normal {
+            return space.over(space.encode(s), destination);
+        }
+
+        // The blended color only replaces the source where there is something below it
+        let d = space.decode(destination);
+        let mix = |s: f32, b: f32| s * (1.0 - d.a) + self.channel(s, b) * d.a;
+
+        let blended = LinearColor::new(mix(s.r, d.r), mix(s.g, d.g), mix(s.b, d.b), s.a);
+        space.over(space.encode(blended), destination)
+    }
+}
+
+/// One canvas of a `LayeredCanvas`
+#[derive(Debug)]
+pub struct Layer {
+    name: String,
+    z: i32,
+    pub canvas: CanvasImpl,
+    pub visible: bool,
+    /// Between 0 and 1
+    pub opacity: f32,
+    pub blend: BlendMode,
+}
+
+impl Layer {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Layers with a higher z are drawn over the others
+    pub fn z(&self) -> i32 {
+        self.z
+    }
+}
+
+/// Stack of canvases that are redrawn independently and composited when presented,
+/// like a background, entities, the UI and debug overlays
+#[derive(Debug)]
+pub struct LayeredCanvas {
+    width: usize,
+    height: usize,
+    layers: Vec<Layer>,
+}
+
+impl LayeredCanvas {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, layers: Vec::new() }
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Adds a transparent layer, or returns the existing layer with this name
+    pub fn add_layer(&mut self, name: &str, z: i32) -> &mut Layer {
+        if let Some(i) = self.position(name) {
+            return &mut self.layers[i];
+        }
+
+        let layer = Layer {
+            name: name.to_string(),
+            z,
+            canvas: CanvasImpl::new(self.width, self.height),
+            visible: true,
+            opacity: 1.0,
+            blend: BlendMode::default(),
+        };
+
+        // After the layers with the same z, so they are drawn in the order they were added
+        let i = self.layers.partition_point(|layer| layer.z <= z);
+        self.layers.insert(i, layer);
+        &mut self.layers[i]
+    }
+
+    pub fn remove_layer(&mut self, name: &str) -> Option<Layer> {
+        let i = self.position(name)?;
+        Some(self.layers.remove(i))
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    /// Canvas of a layer, to draw on it
+    pub fn canvas_mut(&mut self, name: &str) -> Option<&mut CanvasImpl> {
+        Some(&mut self.layer_mut(name)?.canvas)
+    }
+
+    /// Layers from the bottom to the top
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Moves a layer above or below the others
+    pub fn set_z(&mut self, name: &str, z: i32) {
+        let Some(i) = self.position(name) else { return };
+        let mut layer = self.layers.remove(i);
+        layer.z = z;
+
+        let i = self.layers.partition_point(|layer| layer.z <= z);
+        self.layers.insert(i, layer);
+    }
+
+    /// Draws the visible layers over `target`, from the bottom to the top.
+    /// The markers of the visible layers are copied to `target` for one frame so they are still drawn over everything.
+    /// The layers keep their markers, call `tick_markers` once per frame to age them.
+    pub fn composite(&self, target: &mut dyn Canvas) {
+        let (w, h) = target.size_i32();
+        let (w, h) = (w.min(self.width as i32), h.min(self.height as i32));
+
+        for layer in self.layers.iter().filter(|layer| layer.visible) {
+            target.markers_mut().append(&mut layer.canvas.markers().snapshot());
+
+            for y in 0..h {
+                let Some(source) = layer.canvas.pixels(0..w, y) else { continue };
+
+                match target.pixels_mut(0..w, y) {
+                    Some(row) => for (pixel, &color) in row.iter_mut().zip(source) {
+                        *pixel = layer.blend.blend(color, *pixel, layer.opacity);
+                    },
+                    // The target stores another format than `Color`
+                    None => for (x, &color) in (0..).zip(source) {
+                        let Some(pixel) = target.pixel(x, y) else { continue };
+                        target.set_pixel(x, y, layer.blend.blend(color, pixel, layer.opacity));
+                    },
+                }
+            }
+        }
+    }
+
+    /// Composites the layers on a new transparent canvas
+    pub fn present(&self) -> CanvasImpl {
+        let mut canvas = CanvasImpl::new(self.width, self.height);
+        self.composite(&mut canvas);
+
+        canvas
+    }
+
+    /// Ages the markers of every layer, hidden ones included, like rendering them would
+    pub fn tick_markers(&mut self) {
+        for layer in &mut self.layers {
+            layer.canvas.markers_mut().tick();
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use crate::prelude::*;
+
+use super::draw_commands::{CloneCommand, Command, DrawCommand};
+
+/// How long a marker stays on the canvas.
+/// Only rendering the markers of the canvas itself ages them: when it is drawn as an image the copies last one frame
+/// and the markers of the source stay, so a `Frame` marker on a canvas that is never rendered stays until it is cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Lifetime {
+    /// Only the next time the markers are rendered
+    #[default]
+    Frame,
+    /// The next `n` times the markers are rendered
+    Frames(u32),
+    /// Rendered until this much time has passed since it was added
+    Duration(Duration),
+    /// Until the markers or its category are cleared
+    UntilCleared,
+}
+
+/// Command drawn over everything else, for debugging
+#[derive(Debug, Clone)]
+pub struct Marker {
+    command: Box<dyn CloneCommand>,
+    pub lifetime: Lifetime,
+    pub category: Option<String>,
+    created: Instant,
+}
+
+impl Marker {
+    pub fn new<T: DrawCommand, O: Into<T::Options>>(shape: T, options: O) -> Self where <T as DrawCommand>::Command: CloneCommand + 'static {
+        Self::from_command(Box::new(shape.into_renderable(options)))
+    }
+
+    pub fn from_command(command: Box<dyn CloneCommand>) -> Self {
+        Self { command, lifetime: Lifetime::default(), category: None, created: Instant::now() }
+    }
+
+    pub fn lifetime(mut self, lifetime: Lifetime) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Categories can be hidden or cleared together
+    pub fn category(mut self, category: &str) -> Self {
+        self.category = Some(category.to_string());
+        self
+    }
+
+    pub fn command(&self) -> &dyn CloneCommand {
+        &*self.command
+    }
+
+    /// Ages the marker after it was rendered, returns whether it is still alive
+    fn tick(&mut self) -> bool {
+        match &mut self.lifetime {
+            Lifetime::Frame => false,
+            Lifetime::Frames(n) => {
+                *n = n.saturating_sub(1);
+                *n > 0
+            }
+            Lifetime::Duration(duration) => self.created.elapsed() < *duration,
+            Lifetime::UntilCleared => true,
+        }
+    }
+}
+
+impl Command for Marker {
+    fn render_canvas(&mut self, canvas: &mut dyn Canvas) {
+        self.command.render_canvas(canvas);
+    }
+}
+
+impl Transform for Marker {
+    fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
+        self.command.transform(transform);
+    }
+}
+
+/// Markers of a canvas, and the categories that are hidden
+#[derive(Debug, Clone, Default)]
+pub struct Markers {
+    markers: Vec<Marker>,
+    hidden: HashSet<String>,
+}
+
+impl Markers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, marker: Marker) {
+        self.markers.push(marker);
+    }
+
+    /// Moves the markers of `other` in, the ones in a category hidden in `other` stay there
+    pub fn append(&mut self, other: &mut Markers) {
+        let (shown, hidden): (Vec<Marker>, Vec<Marker>) = std::mem::take(&mut other.markers).into_iter().partition(|marker| other.is_shown(marker));
+
+        self.markers.extend(shown);
+        other.markers = hidden;
+    }
+
+    pub fn len(&self) -> usize {
+        self.markers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Marker> {
+        self.markers.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Marker> {
+        self.markers.iter_mut()
+    }
+
+    /// Removes every marker, whatever its lifetime
+    pub fn clear(&mut self) {
+        self.markers.clear();
+    }
+
+    pub fn clear_category(&mut self, category: &str) {
+        self.markers.retain(|marker| marker.category.as_deref() != Some(category));
+    }
+
+    /// Hidden markers are not rendered but still age
+    pub fn set_visible(&mut self, category: &str, visible: bool) {
+        match visible {
+            true => self.hidden.remove(category),
+            false => self.hidden.insert(category.to_string()),
+        };
+    }
+
+    pub fn is_visible(&self, category: &str) -> bool {
+        !self.hidden.contains(category)
+    }
+
+    fn is_shown(&self, marker: &Marker) -> bool {
+        marker.category.as_deref().is_none_or(|category| self.is_visible(category))
+    }
+
+    /// Markers that are not in a hidden category
+    pub fn shown(&self) -> impl Iterator<Item = &Marker> {
+        self.markers.iter().filter(|marker| self.is_shown(marker))
+    }
+
+    /// Copies of the shown markers that only last one frame, for drawing a canvas into another.
+    /// The canvas keeps its markers, so drawing it every frame does not pile them up.
+    pub fn snapshot(&self) -> Markers {
+        let markers = self.shown().map(|marker| marker.clone().lifetime(Lifetime::Frame)).collect();
+        Markers { markers, hidden: HashSet::new() }
+    }
+
+    /// Removes the markers whose lifetime is over, called after they are rendered
+    pub fn tick(&mut self) {
+        self.markers.retain_mut(Marker::tick);
+    }
+}
+
+impl IntoIterator for Markers {
+    type Item = Marker;
+    type IntoIter = std::vec::IntoIter<Marker>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.markers.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Markers {
+    type Item = &'a mut Marker;
+    type IntoIter = std::slice::IterMut<'a, Marker>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.markers.iter_mut()
+    }
+}
+
+impl<'a> IntoIterator for &'a Markers {
+    type Item = &'a Marker;
+    type IntoIter = std::slice::Iter<'a, Marker>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.markers.iter()
+    }
+}
+
+impl FromIterator<Marker> for Markers {
+    fn from_iter<I: IntoIterator<Item = Marker>>(iter: I) -> Self {
+        Self { markers: iter.into_iter().collect(), hidden: HashSet::new() }
+    }
+}
+
+/// Renders the shown markers of `canvas` on it, then ages them.
+/// Shared by the `render_markers` of the canvases.
+pub fn render_markers(canvas: &mut dyn Canvas) {
+    let shown: Vec<Marker> = canvas.markers().shown().cloned().collect();
+
+    for mut marker in shown {
+        marker.render_canvas(canvas);
+    }
+
+    canvas.markers_mut().tick();
+}
@@ -1,12 +1,23 @@
 use std::ops::Range;
 use std::fmt::Debug;
 
-use draw_commands::{background::{Background, BackgroundOptions}, image::ImageOption, line::LineOption, circle::CircleOption, CloneCommand, Command, DrawCommand};
+use draw_commands::{background::{Background, BackgroundOptions}, image::ImageOption, line::LineOption, circle::CircleOption, text::TextOption, flood_fill::{FloodFill, FloodFillOption}, CloneCommand, Command, DrawCommand};
+use marker::{Marker, Markers};
 
 use crate::prelude::*;
 
+pub mod atlas;
 pub mod color;
 pub mod draw_commands;
+pub mod font;
+pub mod image_file;
+pub mod layers;
+pub mod marker;
+pub mod paint;
+pub mod palette;
+pub mod pixel_format;
+pub mod region;
+pub mod sub_canvas;
 
 pub trait Render {
     fn render_context(&self, canvas: &mut dyn Canvas);
@@ -50,7 +61,7 @@ pub struct CanvasImpl {
     width: usize,
     height: usize,
 
-    markers: Vec<Box<dyn CloneCommand>>,
+    markers: Markers,
 }
 
 #[derive(Debug)]
@@ -72,28 +83,20 @@ impl CanvasImpl {
             buffer,
             width,
             height,
-            markers: Vec::new(),
+            markers: Markers::new(),
         }
     }
-
-    pub fn from_image_path(path: &str) -> Self where Self: Sized {
-        let image = image::open(path).unwrap().to_rgba8();
-        let (width, height) = image.dimensions();
-
-        let buffer = image.into_raw();
-        
-        let buffer = buffer.chunks_exact(4)
-            .map(|chunk| u32::from_ne_bytes(chunk.try_into().unwrap()).into())
-            .collect();
-
-        Self::new_buffer(width as usize, height as usize, buffer)
-    }
 }
 
 pub trait Canvas: Debug {
     fn size(&self) -> (usize, usize);
     fn size_i32(&self) -> (i32, i32);
 
+    /// Distance in the buffer between the starts of two rows
+    fn stride(&self) -> usize {
+        self.size().0
+    }
+
     fn index(&self, x: i32, y: i32) -> Option<usize> {
         if x < 0 || y < 0 {
             return None;
@@ -106,43 +109,67 @@ pub trait Canvas: Debug {
             return None;
         }
 
-        Some(y * w + x)
+        Some(y * self.stride() + x)
+    }
+
+    /// Pixels from the top left one, rows start `stride` apart.
+    /// With a stride larger than the width there are pixels between the rows that are not part of the canvas,
+    /// use `pixels` or `to_buffer` to only get the rows.
+    /// Canvases storing another format than `Color` have no buffer, they are only accessed with `pixel` and `set_pixel`.
+    fn buffer(&self) -> &[Color] {
+        &[]
     }
 
-    fn buffer(&self) -> &[Color];
-    fn buffer_mut(&mut self) -> &mut [Color];
+    fn buffer_mut(&mut self) -> &mut [Color] {
+        &mut []
+    }
 
     fn pixels(&self, x: Range<i32>, y: i32) -> Option<&[Color]> {
         let x1 = self.index(x.start, y)?;
         let x2 = self.index(x.end - 1, y)?;
 
-        Some(&self.buffer()[x1..=x2])
+        self.buffer().get(x1..=x2)
     }
 
     fn pixels_mut(&mut self, x: Range<i32>, y: i32) -> Option<&mut [Color]> {
         let x1 = self.index(x.start, y)?;
         let x2 = self.index(x.end - 1, y)?;
 
-        Some(&mut self.buffer_mut()[x1..=x2])
+        self.buffer_mut().get_mut(x1..=x2)
     }
 
     fn pixel(&self, x: i32, y: i32) -> Option<Color> {
-        Some(self.buffer()[self.index(x, y)?])
+        self.buffer().get(self.index(x, y)?).copied()
     }
 
     fn pixel_mut(&mut self, x: i32, y: i32) -> Option<&mut Color> {
         let i = self.index(x, y)?;
-        Some(&mut self.buffer_mut()[i])
+        self.buffer_mut().get_mut(i)
+    }
+
+    /// Overwrites a pixel without blending, nothing happens outside of the canvas
+    fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
+        self.pixel_mut(x, y).color(color);
+    }
+
+    /// Overwrites the part of row `y` inside of the canvas
+    fn set_pixels(&mut self, x: Range<i32>, y: i32, color: Color) {
+        let (w, _) = self.size_i32();
+        let x = x.start.max(0)..x.end.min(w);
+
+        if !x.is_empty() {
+            self.pixels_mut(x, y).color(color);
+        }
     }
 
     fn draw_dyn(&mut self, command: &mut dyn Command);
-    fn marker_dyn(&mut self, marker: Box<dyn CloneCommand>);
+    fn marker_dyn(&mut self, marker: Marker);
 
-    fn markers(&self) -> &Vec<Box<dyn CloneCommand>>;
-    fn markers_mut(&mut self) -> &mut Vec<Box<dyn CloneCommand>>;
+    fn markers(&self) -> &Markers;
+    fn markers_mut(&mut self) -> &mut Markers;
     fn render_markers(&mut self);
 
-    fn get_context(&mut self) -> ContextImpl;
+    fn get_context(&mut self) -> ContextImpl<'_>;
 }
 
 pub trait CanvasGeneric: Canvas {
@@ -151,7 +178,7 @@ pub trait CanvasGeneric: Canvas {
     }
 
     fn marker<T: DrawCommand, O: Into<T::Options>>(&mut self, shape: T, options: O) where <T as DrawCommand>::Command: CloneCommand + 'static {
-        self.marker_dyn(Box::new(shape.into_renderable(options)));
+        self.marker_dyn(Marker::new(shape, options));
     }
 
     fn background(&mut self, color: impl Into<BackgroundOptions>) {
@@ -166,20 +193,113 @@ pub trait CanvasGeneric: Canvas {
         self.draw(image, <Vec2 as Into<ImageOption>>::into(Vec2::new(x, y)).scaling(scale));
     }
 
+    /// Draws one frame of an atlas, the options place it like any other image
+    fn sprite<O: Into<ImageOption>>(&mut self, atlas: &Atlas, frame: usize, options: O) {
+        let Some(rect) = atlas.rect(frame) else { return };
+        self.draw(atlas.image(), options.into().source(rect));
+    }
+
+    /// Draws the current frame of an animation
+    fn animation<O: Into<ImageOption>>(&mut self, atlas: &Atlas, player: &AnimationPlayer, options: O) {
+        let Some(frame) = player.frame(atlas) else { return };
+        self.sprite(atlas, frame, options);
+    }
+
     fn circle<O: Into<CircleOption>>(&mut self, x: i32, y: i32, radius: i32, options: O) {
         self.draw(Circle::new(x, y, radius), options);
     }
 
-    fn save_image_path(&self, path: &str) {
-        let (w, h) = self.size();
-        let mut image = image::ImageBuffer::new(w as u32, h as u32);
+    fn text<O: Into<TextOption>>(&mut self, x: i32, y: i32, text: &str, options: O) {
+        self.draw(Text::new(x, y, text), options);
+    }
 
-        // Iterate over the coordinates and pixels of the image
-        for (pixel, source) in image.pixels_mut().zip(self.buffer().iter()) {
-            *pixel = image::Rgb([source.r(), source.g(), source.b()]);
+    fn text_box<O: Into<TextOption>>(&mut self, rect: Rect, text: &str, options: O) {
+        self.draw(Text::new_box(rect, text), options);
+    }
+
+    /// Copy of the pixels, row after row without the stride
+    fn to_buffer(&self) -> Vec<Color> {
+        let (w, h) = self.size_i32();
+        if self.buffer().is_empty() {
+            return (0..h).flat_map(|y| (0..w).map(move |x| (x, y))).filter_map(|(x, y)| self.pixel(x, y)).collect();
         }
 
-        image.save(path).unwrap();
+        (0..h).flat_map(|y| self.pixels(0..w, y).unwrap_or_default()).copied().collect()
+    }
+
+    /// Copies `rect` of `source` to `x, y`, without blending
+    fn blit(&mut self, source: &dyn Canvas, rect: Rect, x: i32, y: i32) {
+        region::copy_rows(source, rect, self, x, y);
+    }
+
+    /// Copies `rect` to `x, y` of the same canvas, the two can overlap
+    fn copy_region(&mut self, rect: Rect, x: i32, y: i32) {
+        region::copy_within(self, rect, x, y);
+    }
+
+    /// Moves the content by `dx, dy`, the uncovered pixels are set to `fill`
+    fn scroll(&mut self, dx: i32, dy: i32, fill: impl Into<Color>) {
+        let (w, h) = self.size_i32();
+        let fill = fill.into();
+
+        self.copy_region(Rect::new(0, 0, w, h), dx, dy);
+
+        let columns = if dx >= 0 { 0..dx.min(w) } else { (w + dx).max(0)..w };
+        let rows = if dy >= 0 { 0..dy.min(h) } else { (h + dy).max(0)..h };
+
+        for y in 0..h {
+            if rows.contains(&y) {
+                self.set_pixels(0..w, y, fill);
+            } else {
+                self.set_pixels(columns.clone(), y, fill);
+            }
+        }
+    }
+
+    /// Copy of `rect` as a new canvas, transparent where it is outside of this one
+    fn clone_region(&self, rect: Rect) -> CanvasImpl {
+        let (w, h) = rect.size().to_tuple();
+        let mut image = CanvasImpl::new(w.max(0) as usize, h.max(0) as usize);
+
+        region::copy_rows(self, rect, &mut image, 0, 0);
+        image
+    }
+
+    /// Canvas drawing into `rect` of this one, in local coordinates
+    fn sub_canvas(&mut self, rect: Rect) -> SubCanvas<'_> where Self: Sized {
+        SubCanvas::new(self, rect)
+    }
+
+    /// Fills the region of the color of `x, y`, like a paint bucket
+    fn flood_fill<O: Into<FloodFillOption>>(&mut self, x: i32, y: i32, options: O) {
+        self.draw(FloodFill::new(x, y), options);
+    }
+
+    /// Copy of the canvas for processing it with the `image` crate
+    fn to_rgba_image(&self) -> image::RgbaImage {
+        image_file::to_rgba8(self)
+    }
+
+    /// Saves the canvas with alpha, the format is guessed from the extension
+    fn save_image_path(&self, path: &str) -> Result<(), ImageError> {
+        let format = ImageFormat::from_path(path).ok_or_else(|| ImageError::UnknownFormat(path.to_string()))?;
+        self.save_image(path, format)
+    }
+
+    fn save_image(&self, path: &str, format: ImageFormat) -> Result<(), ImageError> {
+        self.write_image(std::io::BufWriter::new(std::fs::File::create(path)?), format)
+    }
+
+    /// Encodes the canvas in memory, like for sending it over the network
+    fn encode_image(&self, format: ImageFormat) -> Result<Vec<u8>, ImageError> {
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        self.write_image(&mut bytes, format)?;
+
+        Ok(bytes.into_inner())
+    }
+
+    fn write_image(&self, writer: impl std::io::Write + std::io::Seek, format: ImageFormat) -> Result<(), ImageError> {
+        image_file::write_image(self, writer, format)
     }
 }
 
@@ -212,29 +332,23 @@ impl Canvas for CanvasImpl {
         command.render_canvas(self);
     }
 
-    fn marker_dyn(&mut self, marker: Box<dyn CloneCommand>) {
+    fn marker_dyn(&mut self, marker: Marker) {
         self.markers.push(marker);
     }
 
-    fn markers_mut(&mut self) -> &mut Vec<Box<dyn CloneCommand>> {
+    fn markers_mut(&mut self) -> &mut Markers {
         &mut self.markers
     }
 
-    fn markers(&self) -> &Vec<Box<dyn CloneCommand>> {
+    fn markers(&self) -> &Markers {
         &self.markers
     }
 
     fn render_markers(&mut self) {
-        let markers = std::mem::take(&mut self.markers);
-
-        for mut marker in markers {
-            marker.render_canvas(self);
-        }
-
-        self.markers_mut().clear();
+        marker::render_markers(self);
     }
 
-    fn get_context(&mut self) -> ContextImpl {
+    fn get_context(&mut self) -> ContextImpl<'_> {
         ContextImpl::new_canvas(self)
     }
 }
@@ -260,10 +374,16 @@ impl<'a> Canvas for ContextImpl<'a> {
     fn buffer(&self) -> &[Color] { self.canvas.buffer() }
     fn buffer_mut(&mut self) -> &mut [Color] { self.canvas.buffer_mut() }
 
+    fn pixel(&self, x: i32, y: i32) -> Option<Color> { self.canvas.pixel(x, y) }
+    fn set_pixel(&mut self, x: i32, y: i32, color: Color) { self.canvas.set_pixel(x, y, color) }
+    fn set_pixels(&mut self, x: Range<i32>, y: i32, color: Color) { self.canvas.set_pixels(x, y, color) }
+
     fn size(&self) -> (usize, usize) { self.canvas.size() }
 
     fn size_i32(&self) -> (i32, i32) { self.canvas.size_i32() }
 
+    fn stride(&self) -> usize { self.canvas.stride() }
+
     fn draw_dyn(&mut self, command: &mut dyn Command) {
         if let Some(transform) = self.view_mut().transform {
             command.transform(&transform);
@@ -272,7 +392,7 @@ impl<'a> Canvas for ContextImpl<'a> {
         command.render_canvas(self);
     }
 
-    fn marker_dyn(&mut self, mut marker: Box<dyn CloneCommand>) {
+    fn marker_dyn(&mut self, mut marker: Marker) {
         if let Some(transform) = self.view_mut().transform {
             marker.transform(&transform);
         }
@@ -280,20 +400,14 @@ impl<'a> Canvas for ContextImpl<'a> {
         self.canvas.markers_mut().push(marker);
     }
 
-    fn markers_mut(&mut self) -> &mut Vec<Box<dyn CloneCommand>> { self.canvas.markers_mut() }
-    fn markers(&self) -> &Vec<Box<dyn CloneCommand>> { self.canvas.markers() }
+    fn markers_mut(&mut self) -> &mut Markers { self.canvas.markers_mut() }
+    fn markers(&self) -> &Markers { self.canvas.markers() }
 
     fn render_markers(&mut self) {
-        let markers = self.markers_mut().clone();
-
-        for mut marker in markers {
-            marker.render_canvas(self);
-        }
-
-        self.markers_mut().clear();
+        marker::render_markers(self);
     }
 
-    fn get_context(&mut self) -> ContextImpl {
+    fn get_context(&mut self) -> ContextImpl<'_> {
         ContextImpl::new_canvas(self)
     }
 }
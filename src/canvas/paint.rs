@@ -0,0 +1,237 @@
+use std::f32::consts::{PI, TAU};
+use std::ops::Range;
+
+use crate::prelude::*;
+
+pub mod texture;
+
+use texture::Texture;
+
+/// What the inside of a shape is filled with
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+    Solid(Color),
+    Gradient(Gradient),
+    Texture(Texture),
+}
+
+impl Paint {
+    pub fn is_transparent(&self) -> bool {
+        matches!(self, Paint::Solid(color) if color.is_transparent())
+    }
+
+    /// Color of the pixel at `x, y`, sampled at its center
+    pub fn color_at(&self, x: i32, y: i32) -> Color {
+        match self {
+            Paint::Solid(color) => *color,
+            Paint::Gradient(gradient) => gradient.color_at(x as f32 + 0.5, y as f32 + 0.5),
+            Paint::Texture(texture) => texture.color_at(x, y),
+        }
+    }
+
+    /// Fills a horizontal run of pixels, skipping the ones outside of the canvas.
+    /// Semi-transparent colors are drawn over the pixels, each pixel of a shape must only be filled once.
+    pub fn fill_span(&self, canvas: &mut dyn Canvas, x: Range<i32>, y: i32) {
+        let (w, _) = canvas.size_i32();
+        let x = x.start.max(0)..x.end.min(w);
+        let space = ColorSpace::default();
+
+        match self {
+            Paint::Solid(color) if color.a() == 255 => canvas.set_pixels(x, y, *color),
+            _ => for x in x {
+                let color = self.color_at(x, y);
+
+                if let Some(pixel) = canvas.pixel(x, y) {
+                    canvas.set_pixel(x, y, space.over(color, pixel));
+                }
+            },
+        }
+    }
+}
+
+impl<C: Into<Color>> From<C> for Paint {
+    fn from(color: C) -> Self {
+        Paint::Solid(color.into())
+    }
+}
+
+impl From<Gradient> for Paint {
+    fn from(gradient: Gradient) -> Self {
+        Paint::Gradient(gradient)
+    }
+}
+
+impl From<Texture> for Paint {
+    fn from(texture: Texture) -> Self {
+        Paint::Texture(texture)
+    }
+}
+
+impl Transform for Paint {
+    fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
+        match self {
+            Paint::Solid(_) => {}
+            Paint::Gradient(gradient) => gradient.transform(transform),
+            Paint::Texture(texture) => texture.transform(transform),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientShape {
+    Linear { start: Vec2, end: Vec2 },
+    /// Horizontal and vertical radius, they differ once the gradient is scaled non-uniformly
+    Radial { center: Vec2, radius: Vec2 },
+    /// Sweeps around the center, starting at `angle` radians, in the other direction once it is flipped
+    Conic { center: Vec2, angle: f32, reversed: bool },
+}
+
+/// What happens outside of the gradient range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Spread {
+    /// Extend the first and last stop
+    #[default]
+    Pad,
+    Repeat,
+    Reflect,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    shape: GradientShape,
+    stops: Vec<(f32, Color)>,
+    spread: Spread,
+    color_space: ColorSpace,
+}
+
+impl Gradient {
+    pub fn new(shape: GradientShape) -> Self {
+        Self {
+            shape,
+            stops: Vec::new(),
+            spread: Spread::default(),
+            color_space: ColorSpace::default(),
+        }
+    }
+
+    pub fn linear(start: Vec2, end: Vec2) -> Self {
+        Self::new(GradientShape::Linear { start, end })
+    }
+
+    pub fn radial(center: Vec2, radius: i32) -> Self {
+        Self::new(GradientShape::Radial { center, radius: Vec2::new(radius, radius) })
+    }
+
+    pub fn conic(center: Vec2, angle: f32) -> Self {
+        Self::new(GradientShape::Conic { center, angle, reversed: false })
+    }
+
+    /// Adds a color at `offset`, between 0 and 1
+    pub fn stop(mut self, offset: f32, color: impl Into<Color>) -> Self {
+        let index = self.stops.partition_point(|(o, _)| *o <= offset);
+        self.stops.insert(index, (offset, color.into()));
+        self
+    }
+
+    pub fn spread(mut self, spread: Spread) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    pub fn shape(&self) -> &GradientShape {
+        &self.shape
+    }
+
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    pub fn color_at(&self, x: f32, y: f32) -> Color {
+        let t = match self.shape {
+            GradientShape::Linear { start, end } => {
+                let (sx, sy) = (*start.x() as f32, *start.y() as f32);
+                let (dx, dy) = (*end.x() as f32 - sx, *end.y() as f32 - sy);
+                let length = dx * dx + dy * dy;
+
+                if length == 0.0 { 0.0 } else { ((x - sx) * dx + (y - sy) * dy) / length }
+            }
+            GradientShape::Radial { center, radius } => {
+                let (rx, ry) = radius.to_tuple();
+                if rx == 0 || ry == 0 {
+                    1.0
+                } else {
+                    let (dx, dy) = ((x - *center.x() as f32) / rx as f32, (y - *center.y() as f32) / ry as f32);
+                    (dx * dx + dy * dy).sqrt()
+                }
+            }
+            GradientShape::Conic { center, angle, reversed } => {
+                let (dx, dy) = (x - *center.x() as f32, y - *center.y() as f32);
+                let sweep = dy.atan2(dx) - angle;
+
+                (if reversed { -sweep } else { sweep }).rem_euclid(TAU) / TAU
+            }
+        };
+
+        let t = match self.spread {
+            Spread::Pad => t.clamp(0.0, 1.0),
+            Spread::Repeat => t.rem_euclid(1.0),
+            Spread::Reflect => 1.0 - (t.rem_euclid(2.0) - 1.0).abs(),
+        };
+
+        self.sample(t)
+    }
+
+    fn sample(&self, t: f32) -> Color {
+        let (Some(first), Some(last)) = (self.stops.first(), self.stops.last()) else {
+            return TRANSPARENT;
+        };
+
+        if t <= first.0 {
+            return first.1;
+        }
+
+        let index = self.stops.partition_point(|(offset, _)| *offset <= t);
+        let Some(&(to_offset, to)) = self.stops.get(index) else {
+            return last.1;
+        };
+
+        let (from_offset, from) = self.stops[index - 1];
+        self.color_space.lerp(from, to, (t - from_offset) / (to_offset - from_offset))
+    }
+}
+
+impl Transform for Gradient {
+    fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
+        match &mut self.shape {
+            GradientShape::Linear { start, end } => {
+                start.transform(transform);
+                end.transform(transform);
+            }
+            GradientShape::Radial { center, radius } => {
+                center.transform(transform);
+
+                let (sx, sy) = transform.scaling().to_tuple();
+                *radius = Vec2::new((radius.x() * sx).abs(), (radius.y() * sy).abs());
+            }
+            GradientShape::Conic { center, angle, reversed } => {
+                center.transform(transform);
+
+                // Mirroring the sweep changes where it starts and its direction
+                let (sx, sy) = transform.scaling().to_tuple();
+                if sx < 0 {
+                    *angle = PI - *angle;
+                    *reversed = !*reversed;
+                }
+                if sy < 0 {
+                    *angle = -*angle;
+                    *reversed = !*reversed;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+/// How texture coordinates outside of the image are mapped back into it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Wrap {
+    #[default]
+    Repeat,
+    /// Extend the edge pixels
+    Clamp,
+    /// Repeat, flipping every other tile
+    Mirror,
+}
+
+impl Wrap {
+    fn apply(self, coordinate: i32, size: i32) -> i32 {
+        match self {
+            Wrap::Repeat => coordinate.rem_euclid(size),
+            Wrap::Clamp => coordinate.clamp(0, size - 1),
+            Wrap::Mirror => {
+                let coordinate = coordinate.rem_euclid(2 * size);
+                if coordinate < size { coordinate } else { 2 * size - 1 - coordinate }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Texture {
+    image: Rc<CanvasImpl>,
+    wrap: Wrap,
+    // Maps texture pixels to canvas pixels
+    transform: Transform2D,
+}
+
+impl Texture {
+    pub fn new(image: Rc<CanvasImpl>) -> Self {
+        Self { image, wrap: Wrap::default(), transform: Transform2D::identity() }
+    }
+
+    /// Snapshot of the current pixels of `canvas`
+    pub fn from_canvas(canvas: &dyn Canvas) -> Self {
+        let (w, h) = canvas.size();
+        Self::new(Rc::new(CanvasImpl::new_buffer(w, h, canvas.to_buffer())))
+    }
+
+    pub fn wrap(mut self, wrap: Wrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Position of the top left corner of the texture and size of each texture pixel
+    pub fn placement(mut self, transform: Transform2D) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    pub fn image(&self) -> &CanvasImpl {
+        &self.image
+    }
+
+    pub fn color_at(&self, x: i32, y: i32) -> Color {
+        let (w, h) = self.image.size_i32();
+        if w == 0 || h == 0 {
+            return TRANSPARENT;
+        }
+
+        let (tx, ty) = self.transform.translation().to_tuple();
+        let (sx, sy) = self.transform.scaling().to_tuple();
+        if sx == 0 || sy == 0 {
+            return TRANSPARENT;
+        }
+
+        let u = self.wrap.apply((x - tx).div_euclid(sx), w);
+        let v = self.wrap.apply((y - ty).div_euclid(sy), h);
+
+        self.image.pixel(u, v).unwrap_or(TRANSPARENT)
+    }
+
+    /// Alternating squares of `size` pixels
+    pub fn checkerboard(size: usize, a: impl Into<Color>, b: impl Into<Color>) -> Self {
+        let (a, b) = (a.into(), b.into());
+        Self::tile(size * 2, size * 2, |x, y| if (x / size + y / size).is_multiple_of(2) { a } else { b })
+    }
+
+    /// Lines every `spacing` pixels, on a transparent background
+    pub fn hatch(hatch: Hatch, spacing: usize, color: impl Into<Color>) -> Self {
+        let color = color.into();
+
+        Self::tile(spacing, spacing, |x, y| {
+            let set = match hatch {
+                Hatch::Horizontal => y == 0,
+                Hatch::Vertical => x == 0,
+                Hatch::Diagonal => x + y == spacing - 1,
+                Hatch::AntiDiagonal => x == y,
+                Hatch::Cross => x == 0 || y == 0,
+                Hatch::DiagonalCross => x == y || x + y == spacing - 1,
+            };
+
+            if set { color } else { TRANSPARENT }
+        })
+    }
+
+    /// Single pixel dots every `spacing` pixels, on a transparent background
+    pub fn dots(spacing: usize, color: impl Into<Color>) -> Self {
+        let color = color.into();
+        Self::tile(spacing, spacing, |x, y| if x == 0 && y == 0 { color } else { TRANSPARENT })
+    }
+
+    fn tile(width: usize, height: usize, color: impl Fn(usize, usize) -> Color) -> Self {
+        let buffer = (0..width * height).map(|i| color(i % width, i / width)).collect();
+        Self::new(Rc::new(CanvasImpl::new_buffer(width, height, buffer)))
+    }
+}
+
+impl PartialEq for Texture {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.image, &other.image) && self.wrap == other.wrap && self.transform == other.transform
+    }
+}
+
+impl Transform for Texture {
+    fn transform(&mut self, transform: &dyn Transformer<i32, 2>) {
+        self.transform.translation_mut().transform(transform);
+        *self.transform.scaling_mut() *= transform.scaling();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hatch {
+    Horizontal,
+    Vertical,
+    /// From bottom left to top right
+    Diagonal,
+    /// From top left to bottom right
+    AntiDiagonal,
+    Cross,
+    DiagonalCross,
+}
@@ -0,0 +1,184 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use crate::prelude::*;
+
+/// Canvas storing palette indices, the palette is only applied when it is presented
+pub type IndexedCanvas = PixelCanvas<Indexed>;
+
+/// Range of palette entries that rotate over time, like water or fire in old games
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PaletteCycle {
+    range: RangeInclusive<u8>,
+    step: Duration,
+    reverse: bool,
+}
+
+impl PaletteCycle {
+    /// The colors of `range` move up by one entry every `step`
+    pub fn new(range: RangeInclusive<u8>, step: Duration) -> Self {
+        Self { range, step, reverse: false }
+    }
+
+    /// The colors move down instead of up
+    pub fn reverse(mut self) -> Self {
+        self.reverse = !self.reverse;
+        self
+    }
+
+    pub fn range(&self) -> &RangeInclusive<u8> {
+        &self.range
+    }
+
+    /// Entry whose color is shown at `index` after `elapsed`
+    fn source(&self, index: u8, elapsed: Duration) -> u8 {
+        if !self.range.contains(&index) || self.step.is_zero() {
+            return index;
+        }
+
+        let (start, len) = (*self.range.start() as u128, self.range.len() as u128);
+        let steps = (elapsed.as_nanos() / self.step.as_nanos()) % len;
+        let shift = if self.reverse { steps } else { len - steps };
+
+        (start + (index as u128 - start + shift) % len) as u8
+    }
+}
+
+/// Up to 256 colors, with the cycles that animate them
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Palette {
+    colors: Vec<Color>,
+    cycles: Vec<PaletteCycle>,
+    elapsed: Duration,
+}
+
+impl Palette {
+    pub fn new(colors: Vec<Color>) -> Self {
+        Self { colors, cycles: Vec::new(), elapsed: Duration::ZERO }
+    }
+
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Color of an entry, ignoring the cycles, missing entries are transparent
+    pub fn color(&self, index: u8) -> Color {
+        self.colors.get(index as usize).copied().unwrap_or(TRANSPARENT)
+    }
+
+    pub fn set_color(&mut self, index: u8, color: impl Into<Color>) {
+        let index = index as usize;
+        if index >= self.colors.len() {
+            self.colors.resize(index + 1, TRANSPARENT);
+        }
+
+        self.colors[index] = color.into();
+    }
+
+    /// Exchanges the colors of two entries
+    pub fn swap(&mut self, a: u8, b: u8) {
+        if (a as usize) < self.colors.len() && (b as usize) < self.colors.len() {
+            self.colors.swap(a as usize, b as usize);
+        }
+    }
+
+    /// Entry closest to `color`, the first one when several are as close
+    pub fn nearest(&self, color: Color) -> u8 {
+        let distance = |other: &Color| {
+            let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            channel(color.r(), other.r()) + channel(color.g(), other.g()) + channel(color.b(), other.b()) + channel(color.a(), other.a())
+        };
+
+        self.colors.iter().take(256).enumerate().min_by_key(|(_, other)| distance(other)).map_or(0, |(i, _)| i as u8)
+    }
+
+    pub fn add_cycle(&mut self, cycle: PaletteCycle) {
+        self.cycles.push(cycle);
+    }
+
+    pub fn cycles(&self) -> &[PaletteCycle] {
+        &self.cycles
+    }
+
+    pub fn clear_cycles(&mut self) {
+        self.cycles.clear();
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Moves the cycles forward
+    pub fn advance(&mut self, delta: Duration) {
+        self.elapsed += delta;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Color shown for an entry, with the cycles applied
+    pub fn resolve(&self, index: u8) -> Color {
+        let index = self.cycles.iter().fold(index, |index, cycle| cycle.source(index, self.elapsed));
+        self.color(index)
+    }
+}
+
+impl From<Vec<Color>> for Palette {
+    fn from(colors: Vec<Color>) -> Self {
+        Self::new(colors)
+    }
+}
+
+impl IndexedCanvas {
+    pub fn palette(&self) -> &Palette {
+        &self.format().palette
+    }
+
+    pub fn palette_mut(&mut self) -> &mut Palette {
+        &mut self.format_mut().palette
+    }
+
+    /// Sets every pixel to `index`
+    pub fn fill(&mut self, index: u8) {
+        self.data_mut().fill(index);
+    }
+
+    /// Copies the indices of another indexed image to `position`, the entries transparent in its palette are skipped.
+    /// Both images are expected to share their palette.
+    pub fn sprite(&mut self, image: &IndexedCanvas, position: Vec2) {
+        self.sprite_swapped(image, &[], position);
+    }
+
+    /// Same as `sprite` with index `i` of the image written as `remap[i]`, like a recolored enemy.
+    /// Indices past the end of `remap` are kept, and the cycles of the palette still apply to the copied indices.
+    pub fn sprite_swapped(&mut self, image: &IndexedCanvas, remap: &[u8], position: Vec2) {
+        let (x, y) = position.to_tuple();
+        let (w, h) = image.size_i32();
+
+        for j in 0..h {
+            for i in 0..w {
+                let index = image.data()[(j * w + i) as usize];
+                if image.palette().color(index).is_transparent() {
+                    continue;
+                }
+
+                let Some(target) = self.index(x + i, y + j) else { continue };
+                self.data_mut()[target] = remap.get(index as usize).copied().unwrap_or(index);
+            }
+        }
+    }
+
+    /// Colors shown on screen, with the palette cycles applied
+    pub fn present(&self) -> CanvasImpl {
+        let (w, h) = self.size();
+        let buffer = self.data().iter().map(|&index| self.palette().resolve(index)).collect();
+
+        CanvasImpl::new_buffer(w, h, buffer)
+    }
+}
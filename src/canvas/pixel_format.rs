@@ -0,0 +1,250 @@
+use std::fmt::Debug;
+use std::ops::Range;
+
+use crate::prelude::*;
+
+use super::draw_commands::Command;
+use super::marker::{self, Marker, Markers};
+
+/// How a color is stored in the buffer of a `PixelCanvas`
+pub trait PixelFormat: Debug + Clone {
+    type Pixel: Copy + Debug + PartialEq;
+
+    fn encode(&self, color: Color) -> Self::Pixel;
+    fn decode(&self, pixel: Self::Pixel) -> Color;
+
+    /// Appends the pixel as it is sent to a display or written to a file
+    fn write_bytes(&self, pixel: Self::Pixel, bytes: &mut Vec<u8>);
+}
+
+/// Same layout as `Color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Argb8888;
+
+impl PixelFormat for Argb8888 {
+    type Pixel = Color;
+
+    fn encode(&self, color: Color) -> Color {
+        color
+    }
+
+    fn decode(&self, pixel: Color) -> Color {
+        pixel
+    }
+
+    fn write_bytes(&self, pixel: Color, bytes: &mut Vec<u8>) {
+        bytes.extend(pixel.as_u32().to_le_bytes());
+    }
+}
+
+/// Bytes in the R, G, B, A order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgba8888;
+
+impl PixelFormat for Rgba8888 {
+    type Pixel = [u8; 4];
+
+    fn encode(&self, color: Color) -> [u8; 4] {
+        [color.r(), color.g(), color.b(), color.a()]
+    }
+
+    fn decode(&self, [r, g, b, a]: [u8; 4]) -> Color {
+        Color::rgba(r, g, b, a)
+    }
+
+    fn write_bytes(&self, pixel: [u8; 4], bytes: &mut Vec<u8>) {
+        bytes.extend(pixel);
+    }
+}
+
+/// 16 bit color used by small displays, without alpha
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb565;
+
+impl PixelFormat for Rgb565 {
+    type Pixel = u16;
+
+    fn encode(&self, color: Color) -> u16 {
+        (color.r() as u16 >> 3) << 11 | (color.g() as u16 >> 2) << 5 | color.b() as u16 >> 3
+    }
+
+    fn decode(&self, pixel: u16) -> Color {
+        let (r, g, b) = ((pixel >> 11) as u8 & 0x1F, (pixel >> 5) as u8 & 0x3F, pixel as u8 & 0x1F);
+
+        // The high bits are repeated in the low ones so white stays white
+        Color::rgb(r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2)
+    }
+
+    /// Big endian, like most SPI displays expect
+    fn write_bytes(&self, pixel: u16, bytes: &mut Vec<u8>) {
+        bytes.extend(pixel.to_be_bytes());
+    }
+}
+
+/// 8 bit luminance, without alpha
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Gray8;
+
+impl PixelFormat for Gray8 {
+    type Pixel = u8;
+
+    fn encode(&self, color: Color) -> u8 {
+        // Rec. 601 luma
+        ((color.r() as u32 * 299 + color.g() as u32 * 587 + color.b() as u32 * 114 + 500) / 1000) as u8
+    }
+
+    fn decode(&self, pixel: u8) -> Color {
+        Color::rgb(pixel, pixel, pixel)
+    }
+
+    fn write_bytes(&self, pixel: u8, bytes: &mut Vec<u8>) {
+        bytes.push(pixel);
+    }
+}
+
+/// Index in a palette of up to 256 colors, colors are stored as the closest entry.
+/// The palette cycles are ignored, they are only applied by `IndexedCanvas::present`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Indexed {
+    pub palette: Palette,
+}
+
+impl Indexed {
+    pub fn new(palette: impl Into<Palette>) -> Self {
+        Self { palette: palette.into() }
+    }
+}
+
+impl PixelFormat for Indexed {
+    type Pixel = u8;
+
+    fn encode(&self, color: Color) -> u8 {
+        self.palette.nearest(color)
+    }
+
+    fn decode(&self, pixel: u8) -> Color {
+        self.palette.color(pixel)
+    }
+
+    fn write_bytes(&self, pixel: u8, bytes: &mut Vec<u8>) {
+        bytes.push(pixel);
+    }
+}
+
+/// Canvas storing its pixels in another format than `Color`.
+/// Commands read and write single pixels through the format, the canvas is never decoded as a whole.
+#[derive(Debug, Clone)]
+pub struct PixelCanvas<F: PixelFormat> {
+    format: F,
+    pixels: Vec<F::Pixel>,
+    width: usize,
+    height: usize,
+
+    markers: Markers,
+}
+
+impl<F: PixelFormat> PixelCanvas<F> {
+    pub fn new(width: usize, height: usize, format: F) -> Self {
+        let pixel = format.encode(TRANSPARENT);
+        Self { pixels: vec![pixel; width * height], format, width, height, markers: Markers::new() }
+    }
+
+    pub fn from_canvas(canvas: &dyn Canvas, format: F) -> Self {
+        let (width, height) = canvas.size();
+        let pixels = canvas.to_buffer().into_iter().map(|color| format.encode(color)).collect();
+
+        Self { format, pixels, width, height, markers: Markers::new() }
+    }
+
+    pub fn format(&self) -> &F {
+        &self.format
+    }
+
+    pub fn format_mut(&mut self) -> &mut F {
+        &mut self.format
+    }
+
+    /// Encoded pixels, row after row
+    pub fn data(&self) -> &[F::Pixel] {
+        &self.pixels
+    }
+
+    pub fn data_mut(&mut self) -> &mut [F::Pixel] {
+        &mut self.pixels
+    }
+
+    /// Decoded copy of the canvas
+    pub fn to_canvas(&self) -> CanvasImpl {
+        let buffer = self.pixels.iter().map(|&pixel| self.format.decode(pixel)).collect();
+        CanvasImpl::new_buffer(self.width, self.height, buffer)
+    }
+
+    /// Same pixels stored in another format, the markers are not copied
+    pub fn convert<G: PixelFormat>(&self, format: G) -> PixelCanvas<G> {
+        let pixels = self.pixels.iter().map(|&pixel| format.encode(self.format.decode(pixel))).collect();
+        PixelCanvas { format, pixels, width: self.width, height: self.height, markers: Markers::new() }
+    }
+
+    /// Buffer in the layout of the format, row after row
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for &pixel in &self.pixels {
+            self.format.write_bytes(pixel, &mut bytes);
+        }
+
+        bytes
+    }
+}
+
+impl<F: PixelFormat> Canvas for PixelCanvas<F> {
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn size_i32(&self) -> (i32, i32) {
+        (self.width as i32, self.height as i32)
+    }
+
+    fn pixel(&self, x: i32, y: i32) -> Option<Color> {
+        Some(self.format.decode(self.pixels[self.index(x, y)?]))
+    }
+
+    fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = self.format.encode(color);
+        }
+    }
+
+    /// The color is only encoded once for the whole run
+    fn set_pixels(&mut self, x: Range<i32>, y: i32, color: Color) {
+        let x = x.start.max(0)..x.end.min(self.width as i32);
+        let (Some(start), Some(end)) = (self.index(x.start, y), self.index(x.end - 1, y)) else { return };
+
+        let pixel = self.format.encode(color);
+        self.pixels[start..=end].fill(pixel);
+    }
+
+    fn draw_dyn(&mut self, command: &mut dyn Command) {
+        command.render_canvas(self);
+    }
+
+    fn marker_dyn(&mut self, marker: Marker) {
+        self.markers.push(marker);
+    }
+
+    fn markers(&self) -> &Markers {
+        &self.markers
+    }
+
+    fn markers_mut(&mut self) -> &mut Markers {
+        &mut self.markers
+    }
+
+    fn render_markers(&mut self) {
+        marker::render_markers(self);
+    }
+
+    fn get_context(&mut self) -> ContextImpl<'_> {
+        ContextImpl::new_canvas(self)
+    }
+}
@@ -0,0 +1,120 @@
+use crate::prelude::*;
+
+/// Part of the canvas that stays in place when resizing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Position of a `size` box anchored inside of `container`
+    pub fn offset(self, container: Vec2, size: Vec2) -> Vec2 {
+        let (free_x, free_y) = (container - size).to_tuple();
+
+        let x = match self {
+            Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => 0,
+            Anchor::Top | Anchor::Center | Anchor::Bottom => free_x / 2,
+            Anchor::TopRight | Anchor::Right | Anchor::BottomRight => free_x,
+        };
+
+        let y = match self {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => 0,
+            Anchor::Left | Anchor::Center | Anchor::Right => free_y / 2,
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => free_y,
+        };
+
+        Vec2::new(x, y)
+    }
+}
+
+impl CanvasImpl {
+    /// Keeps only `rect`, the parts of it outside of the canvas become transparent
+    pub fn crop(&mut self, rect: Rect) {
+        let mut cropped = self.clone_region(rect);
+
+        // The markers move with the content, the hidden categories stay hidden
+        let offset = Transform2D::new(Vec2::new(-rect.x1(), -rect.y1()), Vec2::one());
+        *cropped.markers_mut() = std::mem::take(self.markers_mut());
+        for marker in cropped.markers_mut() {
+            marker.transform(&offset);
+        }
+
+        *self = cropped;
+    }
+
+    /// Changes the size of the canvas without scaling, the content stays at `anchor`
+    pub fn resize(&mut self, width: usize, height: usize, anchor: Anchor) {
+        let size = Vec2::new(width as i32, height as i32);
+        let (w, h) = self.size_i32();
+
+        let offset = anchor.offset(size, Vec2::new(w, h));
+        self.crop(Rect::new(-offset.x(), -offset.y(), *size.x(), *size.y()));
+    }
+}
+
+/// Source and destination positions of a copy clipped to both canvases, and its size.
+/// `x, y` is where the top left corner of `source` goes, even if that corner is clipped.
+fn clip(source: Rect, source_size: (i32, i32), x: i32, y: i32, destination_size: (i32, i32)) -> Option<(Vec2, Vec2, Vec2)> {
+    let clipped = source.intersection(&Rect::new(0, 0, source_size.0, source_size.1))?;
+
+    let destination = Rect::new_vec(Vec2::new(x, y) + *clipped.position() - *source.position(), *clipped.size());
+    let visible = destination.intersection(&Rect::new(0, 0, destination_size.0, destination_size.1))?;
+
+    let shift = *visible.position() - *destination.position();
+    Some((*clipped.position() + shift, *visible.position(), *visible.size()))
+}
+
+/// Copies `rect` of `source` to `x, y` of `destination`, one row at a time
+pub(crate) fn copy_rows<S: Canvas + ?Sized, D: Canvas + ?Sized>(source: &S, rect: Rect, destination: &mut D, x: i32, y: i32) {
+    let Some((from, to, size)) = clip(rect, source.size_i32(), x, y, destination.size_i32()) else { return };
+    let (width, height) = size.to_tuple();
+
+    for j in 0..height {
+        let (sx, sy, tx, ty) = (*from.x(), from.y() + j, *to.x(), to.y() + j);
+        if let (Some(row), Some(target)) = (source.pixels(sx..sx + width, sy), destination.pixels_mut(tx..tx + width, ty)) {
+            target.copy_from_slice(row);
+            continue;
+        }
+
+        // One of the canvases stores another format than `Color`
+        for i in 0..width {
+            let Some(color) = source.pixel(sx + i, sy) else { continue };
+            destination.set_pixel(tx + i, ty, color);
+        }
+    }
+}
+
+/// Same as `copy_rows` inside of one canvas, the regions can overlap
+pub(crate) fn copy_within<C: Canvas + ?Sized>(canvas: &mut C, rect: Rect, x: i32, y: i32) {
+    let size = canvas.size_i32();
+    let Some((from, to, size)) = clip(rect, size, x, y, size) else { return };
+    let (width, height) = size.to_tuple();
+
+    // Rows are copied away from the direction of the move so they are read before being overwritten
+    let rows: Box<dyn Iterator<Item = i32>> = if to.y() > from.y() { Box::new((0..height).rev()) } else { Box::new(0..height) };
+
+    let buffered = !canvas.buffer().is_empty();
+
+    for j in rows {
+        if !buffered {
+            let row: Vec<Color> = (0..width).filter_map(|i| canvas.pixel(from.x() + i, from.y() + j)).collect();
+            for (i, color) in (0..).zip(row) {
+                canvas.set_pixel(to.x() + i, to.y() + j, color);
+            }
+
+            continue;
+        }
+
+        let (Some(start), Some(target)) = (canvas.index(*from.x(), from.y() + j), canvas.index(*to.x(), to.y() + j)) else { continue };
+        canvas.buffer_mut().copy_within(start..start + width as usize, target);
+    }
+}
@@ -0,0 +1,127 @@
+use std::ops::Range;
+
+use crate::prelude::*;
+
+use super::draw_commands::Command;
+use super::marker::{self, Marker, Markers};
+
+/// Part of another canvas, drawn into with its own coordinates.
+/// Drawing outside of it is clipped, and its markers are given to the parent when it is dropped.
+/// Markers in a category hidden on the sub canvas are dropped with it, the parent does not get them.
+#[derive(Debug)]
+pub struct SubCanvas<'a> {
+    parent: &'a mut dyn Canvas,
+    rect: Rect,
+    markers: Markers,
+}
+
+impl<'a> SubCanvas<'a> {
+    /// `rect` is clipped to the parent
+    pub fn new(parent: &'a mut dyn Canvas, rect: Rect) -> Self {
+        let (w, h) = parent.size_i32();
+        let rect = rect.intersection(&Rect::new(0, 0, w, h)).unwrap_or(Rect::new(0, 0, 0, 0));
+
+        Self { parent, rect, markers: Markers::new() }
+    }
+
+    /// Area of the parent covered by this canvas
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// Where the sub canvas starts and ends in the buffer of the parent
+    fn range(&self) -> Range<usize> {
+        let (w, h) = self.size();
+        if w == 0 || h == 0 {
+            return 0..0;
+        }
+
+        let start = self.parent.index(self.rect.x1(), self.rect.y1()).unwrap();
+        start..start + (h - 1) * self.parent.stride() + w
+    }
+}
+
+impl<'a> Canvas for SubCanvas<'a> {
+    fn size(&self) -> (usize, usize) {
+        let (w, h) = self.rect.size().to_tuple();
+        (w as usize, h as usize)
+    }
+
+    fn size_i32(&self) -> (i32, i32) {
+        self.rect.size().to_tuple()
+    }
+
+    fn stride(&self) -> usize {
+        self.parent.stride()
+    }
+
+    /// Rows of the parent from the first to the last one of this canvas,
+    /// the pixels of the parent on the left and right of the rect are in it too
+    fn buffer(&self) -> &[Color] {
+        let range = self.range();
+        self.parent.buffer().get(range).unwrap_or_default()
+    }
+
+    fn buffer_mut(&mut self) -> &mut [Color] {
+        let range = self.range();
+        self.parent.buffer_mut().get_mut(range).unwrap_or_default()
+    }
+
+    /// Goes through the parent, which may store another format than `Color`
+    fn pixel(&self, x: i32, y: i32) -> Option<Color> {
+        self.index(x, y)?;
+        self.parent.pixel(self.rect.x1() + x, self.rect.y1() + y)
+    }
+
+    fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
+        if self.index(x, y).is_some() {
+            self.parent.set_pixel(self.rect.x1() + x, self.rect.y1() + y, color);
+        }
+    }
+
+    fn set_pixels(&mut self, x: Range<i32>, y: i32, color: Color) {
+        let (w, h) = self.size_i32();
+        let x = x.start.max(0)..x.end.min(w);
+
+        if !x.is_empty() && (0..h).contains(&y) {
+            let (ox, oy) = (self.rect.x1(), self.rect.y1());
+            self.parent.set_pixels(ox + x.start..ox + x.end, oy + y, color);
+        }
+    }
+
+    fn draw_dyn(&mut self, command: &mut dyn Command) {
+        command.render_canvas(self);
+    }
+
+    fn marker_dyn(&mut self, marker: Marker) {
+        self.markers.push(marker);
+    }
+
+    fn markers(&self) -> &Markers {
+        &self.markers
+    }
+
+    fn markers_mut(&mut self) -> &mut Markers {
+        &mut self.markers
+    }
+
+    fn render_markers(&mut self) {
+        marker::render_markers(self);
+    }
+
+    fn get_context(&mut self) -> ContextImpl<'_> {
+        ContextImpl::new_canvas(self)
+    }
+}
+
+impl<'a> Drop for SubCanvas<'a> {
+    fn drop(&mut self) {
+        let offset = Transform2D::new(*self.rect.position(), Vec2::one());
+
+        let markers = std::mem::take(&mut self.markers);
+        for mut marker in markers.shown().cloned() {
+            marker.transform(&offset);
+            self.parent.markers_mut().push(marker);
+        }
+    }
+}
@@ -20,6 +20,14 @@ impl<T: Number> Rect<T> {
 }
 
 impl<T: Number> Rect<T> {
+    pub fn position(&self) -> &Vec2<T> {
+        &self.position
+    }
+
+    pub fn size(&self) -> &Vec2<T> {
+        &self.size
+    }
+
     pub fn x1(&self) -> T {
         *self.position.x()
     }
@@ -37,6 +45,27 @@ impl<T: Number> Rect<T> {
     }
 }
 
+impl<T: Number + PartialOrd + std::ops::Sub<Output = T>> Rect<T> {
+    pub fn contains(&self, x: T, y: T) -> bool {
+        x >= self.x1() && x < self.x2() && y >= self.y1() && y < self.y2()
+    }
+
+    /// Overlapping area of both rectangles, if any
+    pub fn intersection(&self, other: &Rect<T>) -> Option<Rect<T>> {
+        let max = |a: T, b: T| if a > b { a } else { b };
+        let min = |a: T, b: T| if a < b { a } else { b };
+
+        let (x1, y1) = (max(self.x1(), other.x1()), max(self.y1(), other.y1()));
+        let (x2, y2) = (min(self.x2(), other.x2()), min(self.y2(), other.y2()));
+
+        if x1 >= x2 || y1 >= y2 {
+            return None;
+        }
+
+        Some(Rect::new(x1, y1, x2 - x1, y2 - y1))
+    }
+}
+
 impl<T: Number> Transform<T, 2> for Rect<T> {
     fn transform(&mut self, transform: &dyn Transformer<T, 2>) {
         self.position.transform(transform);
@@ -4,9 +4,18 @@ pub use crate::math::vector::Vec2;
 pub use crate::math::shapes::{line::Line, rect::Rect, triangle::Triangle, circle::Circle};
 pub use crate::math::transform::{Transformer, Transform2D, Transform};
 
-pub use crate::canvas::{Canvas, CanvasGeneric, CanvasImpl, Context, ContextImpl, ContextGeneric};
-pub use crate::canvas::{color::*, Render, Coloring};
-pub use crate::canvas::draw_commands::{line::LineOptionTrait, circle::CircleOptionTrait};
+pub use crate::canvas::{Canvas, CanvasGeneric, CanvasImpl, Context, ContextImpl, ContextGeneric, sub_canvas::SubCanvas, region::Anchor};
+pub use crate::canvas::{color::*, Render, Coloring, image_file::{ImageError, ImageFormat}};
+pub use crate::canvas::color::linear::{LinearColor, ColorSpace, ToneMap};
+pub use crate::canvas::paint::{Paint, Gradient, GradientShape, Spread, texture::{Texture, Wrap, Hatch}};
+pub use crate::canvas::draw_commands::{line::LineOptionTrait, circle::CircleOptionTrait, text::{Text, TextOptionTrait}, image::{ImageOption, Filter, AlphaMode}, flood_fill::{FloodFill, FloodFillOptionTrait, Connectivity}, marker_style::{MarkerStyle, MarkerStyleOptionTrait}};
+pub use crate::canvas::marker::{Marker, Markers, Lifetime};
+pub use crate::canvas::pixel_format::{PixelFormat, PixelCanvas, Argb8888, Rgba8888, Rgb565, Gray8, Indexed};
+pub use crate::canvas::palette::{Palette, PaletteCycle, IndexedCanvas};
+pub use crate::canvas::layers::{LayeredCanvas, Layer, BlendMode};
+pub use crate::canvas::atlas::{Atlas, Animation, AnimationPlayer, LoopMode, aseprite::{Aseprite, AsepriteError}};
+pub use crate::canvas::font::{Font, FontError, Glyph, PageColors, bitmap::BitmapFont, truetype::TrueTypeFont};
+pub use crate::canvas::font::layout::{Align, VerticalAlign, LayoutOptions, TextLayout};
 
 pub use crate::window::{App, Window, WindowTrait};
 pub use crate::window::event::{Event, MouseButton, MouseEvent, MouseMoveEvent, MouseWheelEvent, KeyDownEvent, KeyModifiers, Key};
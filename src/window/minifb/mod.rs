@@ -8,6 +8,8 @@ pub struct Window {
     window: minifb::Window,
     keyboard: Rc<RefCell<KeyboardManager>>,
     mouse: MouseManager,
+    // Set when the app presented a frame itself, the canvas of the main loop is not shown then
+    presented: bool,
 }
 
 impl From<minifb::Key> for Key {
@@ -144,12 +146,35 @@ impl Window {
         canvas.render_markers();
 
         // TODO: Do it better
-        let buffer: Vec<u32> = canvas.buffer().into_iter().map(|color| color.as_u32()).collect();
+        let buffer: Vec<u32> = canvas.buffer().iter().map(|color| color.as_u32()).collect();
 
-        assert_eq!(buffer.len(), self.window.get_size().0 * self.window.get_size().1);
+        let (w, h) = canvas.size();
+        self.present(&buffer, w, h);
+    }
+
+    /// Resolves the indices with the palette of the canvas, cycles included.
+    /// Called from `App::render`, it is shown instead of the canvas of the main loop for this frame.
+    pub fn render_indexed(&mut self, canvas: &IndexedCanvas) {
+        let palette = canvas.palette();
+        let buffer: Vec<u32> = canvas.data().iter().map(|&index| palette.resolve(index).as_u32()).collect();
 
         let (w, h) = canvas.size();
-        self.window.update_with_buffer(&buffer, w, h).unwrap();
+        self.present(&buffer, w, h);
+    }
+
+    /// Composites the visible layers, their markers are drawn over all of them.
+    /// Called from `App::render`, it is shown instead of the canvas of the main loop for this frame,
+    /// `LayeredCanvas::composite` draws the layers on that canvas instead, then `LayeredCanvas::tick_markers` has to be called.
+    pub fn render_layers(&mut self, layers: &mut LayeredCanvas) {
+        self.render(&mut layers.present());
+        layers.tick_markers();
+    }
+
+    fn present(&mut self, buffer: &[u32], width: usize, height: usize) {
+        assert_eq!(buffer.len(), self.window.get_size().0 * self.window.get_size().1);
+
+        self.window.update_with_buffer(buffer, width, height).unwrap();
+        self.presented = true;
     }
 }
 
@@ -181,7 +206,8 @@ impl WindowTrait for Window {
         let mut window = Self {
             window: fb_window,
             keyboard,
-            mouse
+            mouse,
+            presented: false,
         };
 
         let mut canvas = CanvasImpl::new(width, height);
@@ -202,8 +228,14 @@ impl WindowTrait for Window {
             window.keyboard.borrow_mut().update();
             window.mouse.update();
 
-            // Render and wait, keyboard event are handled by callback
-            window.render(&mut canvas);            
+            // Render and wait, keyboard event are handled by callback.
+            // The app may have presented another frame source, only one frame is shown per update.
+            if window.presented {
+                canvas.markers_mut().tick();
+            } else {
+                window.render(&mut canvas);
+            }
+            window.presented = false;
         }
     }
 
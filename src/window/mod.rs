@@ -6,6 +6,7 @@ mod minifb;
 pub use minifb::Window;
 
 pub trait WindowTrait {
+    #[allow(clippy::new_ret_no_self)]
     fn new(title: &str, width: usize, height: usize, app: impl App);
     
     fn is_open(&self) -> bool;
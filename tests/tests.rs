@@ -1,4 +1,914 @@
 #[test]
 fn it_works() {
     assert_eq!(2 + 2, 4);
-}
\ No newline at end of file
+}
+
+#[test]
+fn text_layout_wraps_and_truncates() {
+    use frender::prelude::*;
+
+    let font = frender::canvas::font::builtin();
+    let options = LayoutOptions { ellipsis: true, ..Default::default() };
+    let layout = TextLayout::new(&*font, "the quick brown fox", Some(Vec2::new(60, 20)), &options);
+
+    let lines: Vec<_> = layout.lines().iter().map(|line| line.text()).collect();
+    assert_eq!(lines, ["the quick", "brown fox"]);
+
+    let layout = TextLayout::new(&*font, "the quick brown fox", Some(Vec2::new(60, 10)), &options);
+    assert_eq!(layout.lines().len(), 1);
+    assert_eq!(layout.lines()[0].text(), "the qui...");
+
+    // Nothing fits in a box lower than a line
+    let layout = TextLayout::new(&*font, "the quick brown fox", Some(Vec2::new(60, 5)), &options);
+    assert!(layout.lines().is_empty());
+
+    // Lines still move down when the spacing cancels the line height
+    let options = LayoutOptions { ellipsis: true, line_spacing: -font.line_height(), ..Default::default() };
+    let layout = TextLayout::new(&*font, "the quick brown fox", Some(Vec2::new(60, 20)), &options);
+    assert_eq!(layout.lines()[1].position().y() - layout.lines()[0].position().y(), 1);
+}
+
+#[test]
+fn color_parsing_and_conversion() {
+    use frender::prelude::*;
+
+    assert_eq!("#f00".parse::<Color>(), Ok(RED));
+    assert_eq!("#FF000080".parse::<Color>(), Ok(RED.with_alpha(0x80)));
+    assert_eq!("cornflowerblue".parse::<Color>(), Ok(Color::rgb(100, 149, 237)));
+    assert_eq!("lime".parse::<Color>(), Ok(LIME));
+    assert_eq!("green".parse::<Color>(), Ok(named::GREEN));
+    assert!("#12345".parse::<Color>().is_err());
+    assert!("#+FFFFF".parse::<Color>().is_err());
+    assert!(Color::from_hex("+FFF").is_err());
+    assert!(Color::from_hex("#").is_err());
+    assert_eq!(Color::rgba(1, 2, 3, 4).to_string(), "#01020304");
+
+    let color = Color::rgb(200, 100, 50);
+    let (h, s, v) = color.to_hsv();
+    assert_eq!(Color::from_hsv(h, s, v), color);
+    let (h, s, l) = color.to_hsl();
+    assert_eq!(Color::from_hsl(h, s, l), color);
+
+    assert_eq!(BLACK.lerp(WHITE, 0.5), Color::rgb(128, 128, 128));
+}
+
+#[test]
+fn linear_color_round_trip() {
+    use frender::prelude::*;
+
+    for v in 0..=255 {
+        let color = Color::rgba(v, 255 - v, v / 2, v);
+        assert_eq!(LinearColor::from(color).to_srgb(), color);
+    }
+
+    // Half way between black and white is brighter in linear light
+    assert_eq!(ColorSpace::Linear.lerp(BLACK, WHITE, 0.5), Color::rgb(188, 188, 188));
+}
+
+#[test]
+fn image_source_rect() {
+    use frender::prelude::*;
+
+    // Two 2x2 sprites side by side
+    let mut sheet = CanvasImpl::new(4, 2);
+    sheet.pixels_mut(0..2, 0).color(RED);
+    sheet.pixels_mut(0..2, 1).color(RED);
+    sheet.pixels_mut(2..4, 0).color(BLUE);
+    sheet.pixels_mut(2..4, 1).color(BLUE);
+
+    let mut canvas = CanvasImpl::new(4, 4);
+    let options = ImageOption::from(Vec2::new(0, 0)).source(Rect::new(2, 0, 2, 2)).scaling(2).filter(Filter::Bilinear);
+    canvas.draw(&sheet, options);
+
+    assert!(canvas.buffer().iter().all(|color| *color == BLUE));
+}
+
+#[test]
+fn image_flip_and_rotation() {
+    use frender::prelude::*;
+
+    let mut image = CanvasImpl::new(3, 1);
+    image.pixels_mut(0..3, 0).color(WHITE);
+    *image.pixel_mut(0, 0).unwrap() = RED;
+
+    let mut canvas = CanvasImpl::new(3, 3);
+    canvas.draw(&image, ImageOption::from(Vec2::new(0, 0)).flip_horizontal());
+    assert_eq!(canvas.pixel(2, 0), Some(RED));
+
+    let mut canvas = CanvasImpl::new(3, 3);
+    canvas.draw(&image, ImageOption::from(Vec2::new(0, 0)).rotate_quarter(1));
+    assert_eq!(canvas.pixel(0, 0), Some(RED));
+    assert_eq!(canvas.pixel(0, 2), Some(WHITE));
+    assert_eq!(canvas.pixel(1, 0), Some(TRANSPARENT));
+
+    // A quarter of a turn around the center of the middle pixel
+    let mut canvas = CanvasImpl::new(3, 3);
+    canvas.draw(&image, ImageOption::from(Vec2::new(0, 1)).rotate(std::f32::consts::FRAC_PI_2));
+    assert_eq!(canvas.pixel(1, 0), Some(RED));
+    assert_eq!(canvas.pixel(1, 2), Some(WHITE));
+    assert_eq!(canvas.pixel(0, 1), Some(TRANSPARENT));
+}
+
+#[test]
+fn image_alpha_modes() {
+    use frender::prelude::*;
+
+    let mut sprite = CanvasImpl::new(3, 1);
+    *sprite.pixel_mut(0, 0).unwrap() = RED;
+    *sprite.pixel_mut(1, 0).unwrap() = Color::rgb(255, 0, 255);
+
+    let mut canvas = CanvasImpl::new(3, 1);
+    canvas.pixels_mut(0..3, 0).color(BLUE);
+    canvas.draw(&sprite, ImageOption::from(Vec2::new(0, 0)).color_key(Color::rgb(255, 0, 255)));
+    assert_eq!(canvas.buffer(), [RED, BLUE, BLUE]);
+
+    canvas.draw(&sprite, ImageOption::from(Vec2::new(0, 0)).alpha(AlphaMode::Copy));
+    assert_eq!(canvas.pixel(2, 0), Some(TRANSPARENT));
+
+    canvas.pixels_mut(0..3, 0).color(BLUE);
+    canvas.draw(&sprite, ImageOption::from(Vec2::new(0, 0)).opacity(0.5));
+    assert_eq!(canvas.pixel(0, 0), Some(Color::rgb(128, 0, 127)));
+    assert_eq!(canvas.pixel(2, 0), Some(BLUE));
+
+    canvas.draw(&sprite, ImageOption::from(Vec2::new(0, 0)).alpha(AlphaMode::Skip).tint(Color::rgb(0, 255, 255)));
+    assert_eq!(canvas.pixel(0, 0), Some(BLACK));
+}
+
+#[test]
+fn atlas_animation() {
+    use std::time::Duration;
+    use frender::prelude::*;
+
+    let mut sheet = CanvasImpl::new(6, 2);
+    sheet.pixels_mut(2..4, 0).color(RED);
+    let mut atlas = Atlas::from_grid(sheet, 2, 2);
+    assert_eq!(atlas.frames().len(), 3);
+    assert_eq!(atlas.rect(atlas.frame("1").unwrap()), Some(Rect::new(2, 0, 2, 2)));
+
+    let step = Duration::from_millis(100);
+    atlas.add_animation("walk", Animation::from_frames(0..3, step, LoopMode::PingPong));
+    let animation = atlas.animation("walk").unwrap();
+    let frames: Vec<_> = (0..6).map(|i| animation.frame_at(step * i).unwrap()).collect();
+    assert_eq!(frames, [0, 1, 2, 1, 0, 1]);
+
+    let mut player = AnimationPlayer::new("walk");
+    player.advance(step);
+
+    let mut canvas = CanvasImpl::new(2, 2);
+    canvas.animation(&atlas, &player, Vec2::new(0, 0));
+    assert_eq!(canvas.pixel(1, 0), Some(RED));
+}
+
+#[test]
+fn aseprite_import() {
+    use std::io::Write;
+    use std::time::Duration;
+    use frender::prelude::*;
+
+    fn chunk(kind: u16, data: &[u8]) -> Vec<u8> {
+        [&(data.len() as u32 + 6).to_le_bytes()[..], &kind.to_le_bytes(), data].concat()
+    }
+
+    fn frame(duration: u16, chunks: &[Vec<u8>]) -> Vec<u8> {
+        let data = chunks.concat();
+        let header = [&(data.len() as u32 + 16).to_le_bytes()[..], &0xF1FAu16.to_le_bytes(), &(chunks.len() as u16).to_le_bytes(), &duration.to_le_bytes(), &[0; 6]].concat();
+        [header, data].concat()
+    }
+
+    fn cel(kind: u16, pixels: &[u8]) -> Vec<u8> {
+        [&[0, 0, 0, 0, 0, 0, 255][..], &kind.to_le_bytes(), &[0; 7], &2u16.to_le_bytes(), &1u16.to_le_bytes(), pixels].concat()
+    }
+
+    let layer = [&[1, 0, 0, 0, 0, 0][..], &[0; 6], &[255, 0, 0, 0], &4u16.to_le_bytes(), b"body"].concat();
+    let tags = [&1u16.to_le_bytes()[..], &[0; 8], &[0, 0, 1, 0, 2, 0, 0], &[0; 10], &4u16.to_le_bytes(), b"walk"].concat();
+
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&[255, 0, 0, 255, 0, 0, 0, 0]).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let frames = [
+        frame(100, &[chunk(0x2004, &layer), chunk(0x2018, &tags), chunk(0x2005, &cel(2, &compressed))]),
+        frame(50, &[chunk(0x2005, &cel(0, &[0, 0, 255, 255, 0, 0, 255, 128]))]),
+    ].concat();
+
+    let header = [&((128 + frames.len()) as u32).to_le_bytes()[..], &0xA5E0u16.to_le_bytes(), &2u16.to_le_bytes(), &2u16.to_le_bytes(), &1u16.to_le_bytes(), &32u16.to_le_bytes(), &1u32.to_le_bytes(), &[0; 110]].concat();
+    let sprite = Aseprite::from_bytes(&[header, frames].concat()).unwrap();
+
+    assert_eq!(sprite.layers[0].name, "body");
+    assert_eq!(sprite.frames[1].duration, Duration::from_millis(50));
+    assert_eq!(sprite.frame_image(0).buffer(), [RED, TRANSPARENT]);
+    assert_eq!(sprite.frame_image(1).buffer(), [BLUE, BLUE.with_alpha(128)]);
+
+    let atlas = sprite.to_atlas();
+    let animation = atlas.animation("walk").unwrap();
+    assert_eq!(animation.mode(), LoopMode::Loop);
+    assert_eq!(animation.frames(), [(0, Duration::from_millis(100)), (1, Duration::from_millis(50))]);
+
+    let file = |depth: u16, chunks: &[Vec<u8>]| {
+        let frames = frame(100, chunks);
+        let header = [&((128 + frames.len()) as u32).to_le_bytes()[..], &0xA5E0u16.to_le_bytes(), &1u16.to_le_bytes(), &2u16.to_le_bytes(), &1u16.to_le_bytes(), &depth.to_le_bytes(), &1u32.to_le_bytes(), &[0; 110]].concat();
+        [header, frames].concat()
+    };
+
+    // Tags can only refer to existing frames
+    let tags = [&1u16.to_le_bytes()[..], &[0; 8], &[0, 0, 5, 0, 0, 0, 0], &[0; 10], &4u16.to_le_bytes(), b"walk"].concat();
+    assert!(matches!(Aseprite::from_bytes(&file(32, &[chunk(0x2018, &tags)])), Err(AsepriteError::Invalid(_))));
+
+    // Indexed palettes have at most 256 colors, whatever size the file claims
+    let palette = |size: u32| [&size.to_le_bytes()[..], &0u32.to_le_bytes(), &0u32.to_le_bytes(), &[0; 8], &[0, 0, 255, 0, 0, 255]].concat();
+    assert!(matches!(Aseprite::from_bytes(&file(8, &[chunk(0x2019, &palette(u32::MAX))])), Err(AsepriteError::Invalid(_))));
+    let sprite = Aseprite::from_bytes(&file(32, &[chunk(0x2019, &palette(u32::MAX))])).unwrap();
+    assert_eq!(sprite.palette.len(), 256);
+    assert_eq!(sprite.palette[0], RED);
+}
+
+#[test]
+fn image_loading() {
+    use frender::prelude::*;
+
+    let image = image::RgbaImage::from_raw(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+    let mut png = Vec::new();
+    image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+
+    let canvas = CanvasImpl::from_image_bytes(&png).unwrap();
+    assert_eq!(canvas.buffer(), [RED, BLUE.with_alpha(128)]);
+
+    assert!(matches!(CanvasImpl::from_image_bytes(b"not an image"), Err(ImageError::Codec(_))));
+    assert!(matches!(CanvasImpl::from_image_path("missing.png"), Err(ImageError::Io(_))));
+}
+
+#[test]
+fn image_export() {
+    use frender::prelude::*;
+
+    let mut canvas = CanvasImpl::new(2, 1);
+    *canvas.pixel_mut(0, 0).unwrap() = RED.with_alpha(64);
+
+    for format in [ImageFormat::Png, ImageFormat::Bmp, ImageFormat::Qoi, ImageFormat::Tiff] {
+        let bytes = canvas.encode_image(format).unwrap();
+        assert_eq!(CanvasImpl::from_image_bytes(&bytes).unwrap().buffer(), canvas.buffer(), "{format:?}");
+    }
+
+    assert_eq!(ImageFormat::from_path("out/sprite.PNG"), Some(ImageFormat::Png));
+    assert!(matches!(canvas.save_image_path("out/sprite.unknown"), Err(ImageError::UnknownFormat(_))));
+}
+
+#[test]
+fn image_crate_interop() {
+    use frender::prelude::*;
+
+    let image = image::RgbaImage::from_raw(2, 1, vec![255, 0, 0, 255, 0, 0, 255, 128]).unwrap();
+    let canvas = CanvasImpl::from(image.clone());
+    assert_eq!(canvas.buffer(), [RED, BLUE.with_alpha(128)]);
+    assert_eq!(canvas.to_rgba_image(), image);
+
+    let gray = image::DynamicImage::ImageLuma8(image::GrayImage::from_raw(1, 1, vec![255]).unwrap());
+    assert_eq!(CanvasImpl::from(gray).buffer(), [WHITE]);
+
+    assert_eq!(canvas.buffer().to_bgra_bytes(), [0, 0, 255, 255, 255, 0, 0, 128]);
+    #[cfg(target_endian = "little")]
+    assert_eq!(canvas.buffer().as_bgra_bytes(), canvas.buffer().to_bgra_bytes());
+    assert_eq!(CanvasImpl::from_rgba_bytes(2, 1, &canvas.buffer().to_rgba_bytes()).unwrap().buffer(), canvas.buffer());
+}
+
+#[test]
+fn flood_fill_regions() {
+    use frender::prelude::*;
+
+    // The top left corner only touches the rest diagonally
+    let mut walls = CanvasImpl::new(4, 4);
+    *walls.pixel_mut(1, 0).unwrap() = BLACK;
+    *walls.pixel_mut(0, 1).unwrap() = BLACK;
+    *walls.pixel_mut(3, 3).unwrap() = Color::rgba(0, 0, 5, 5);
+
+    // The same diagonal gap stops a 4-connected fill and lets an 8-connected one through
+    let mut canvas = CanvasImpl::new(4, 4);
+    canvas.draw(&walls, ImageOption::from(Vec2::new(0, 0)).alpha(AlphaMode::Copy));
+    canvas.flood_fill(0, 0, RED.connectivity(Connectivity::Four));
+    assert_eq!(canvas.buffer().iter().filter(|color| **color == RED).count(), 1);
+    assert_eq!(canvas.pixel(1, 1), Some(TRANSPARENT));
+
+    let mut canvas = CanvasImpl::new(4, 4);
+    canvas.draw(&walls, ImageOption::from(Vec2::new(0, 0)).alpha(AlphaMode::Copy));
+    canvas.flood_fill(0, 0, RED.connectivity(Connectivity::Eight));
+    assert_eq!(canvas.buffer().iter().filter(|color| **color == RED).count(), 13);
+    assert_eq!(canvas.pixel(1, 1), Some(RED));
+    assert_eq!(canvas.pixel(1, 0), Some(BLACK));
+
+    let mut canvas = CanvasImpl::new(4, 4);
+    canvas.draw(&walls, ImageOption::from(Vec2::new(0, 0)).alpha(AlphaMode::Copy));
+    canvas.flood_fill(1, 1, LIME.tolerance(5));
+    assert_eq!(canvas.buffer().iter().filter(|color| **color == LIME).count(), 13);
+}
+
+#[test]
+fn sub_canvas_region() {
+    use frender::prelude::*;
+
+    let mut canvas = CanvasImpl::new(6, 4);
+    {
+        let mut tile = canvas.sub_canvas(Rect::new(2, 1, 10, 2));
+        assert_eq!(tile.size(), (4, 2));
+        tile.background(RED);
+        tile.marker(Line::new(0, 0, 0, 0), BLUE);
+
+        let mut inner = SubCanvas::new(&mut tile, Rect::new(1, 1, 1, 1));
+        inner.background(LIME);
+        assert_eq!(inner.to_buffer(), [LIME]);
+    }
+
+    assert_eq!(canvas.pixel(1, 1), Some(TRANSPARENT));
+    assert_eq!(canvas.pixel(2, 1), Some(RED));
+    assert_eq!(canvas.pixel(5, 2), Some(RED));
+    assert_eq!(canvas.pixel(3, 2), Some(LIME));
+    assert_eq!(canvas.pixel(2, 3), Some(TRANSPARENT));
+
+    canvas.render_markers();
+    assert_eq!(canvas.pixel(2, 1), Some(BLUE));
+
+    // A context keeps the stride of the sub canvas, the parent pixels around the rect stay untouched
+    let mut canvas = CanvasImpl::new(6, 4);
+    {
+        let mut tile = canvas.sub_canvas(Rect::new(1, 1, 3, 2));
+        let mut context = tile.get_context();
+        context.background(RED);
+        context.line(0, 1, 2, 1, LIME);
+    }
+
+    for y in 1..3 {
+        assert_eq!(canvas.pixel(0, y), Some(TRANSPARENT));
+        assert_eq!(canvas.pixel(4, y), Some(TRANSPARENT));
+    }
+    assert_eq!(canvas.pixel(1, 1), Some(RED));
+    assert_eq!(canvas.pixels(1..4, 2), Some(&[LIME; 3][..]));
+    assert_eq!(canvas.pixel(1, 3), Some(TRANSPARENT));
+}
+
+#[test]
+fn canvas_regions() {
+    use frender::prelude::*;
+
+    let colors = [RED, LIME, BLUE, WHITE];
+    let mut canvas = CanvasImpl::new_buffer(2, 2, colors.to_vec());
+
+    assert_eq!(canvas.clone_region(Rect::new(1, 0, 2, 1)).buffer(), [LIME, TRANSPARENT]);
+
+    let mut copy = CanvasImpl::new(3, 3);
+    copy.blit(&canvas, Rect::new(-1, -1, 3, 3), 0, 0);
+    assert_eq!(copy.to_buffer()[4..], [RED, LIME, TRANSPARENT, BLUE, WHITE]);
+
+    copy.copy_region(Rect::new(1, 1, 2, 2), 0, 0);
+    assert_eq!(copy.to_buffer()[..5], [RED, LIME, TRANSPARENT, BLUE, WHITE]);
+
+    canvas.scroll(0, 1, BLACK);
+    assert_eq!(canvas.buffer(), [BLACK, BLACK, RED, LIME]);
+    canvas.scroll(-1, 0, BLACK);
+    assert_eq!(canvas.buffer(), [BLACK, BLACK, LIME, BLACK]);
+
+    let mut canvas = CanvasImpl::new_buffer(2, 2, colors.to_vec());
+    canvas.resize(4, 3, Anchor::BottomRight);
+    assert_eq!(canvas.size(), (4, 3));
+    assert_eq!(canvas.pixel(2, 1), Some(RED));
+    assert_eq!(canvas.pixel(3, 2), Some(WHITE));
+
+    canvas.crop(Rect::new(3, 1, 1, 2));
+    assert_eq!(canvas.buffer(), [LIME, WHITE]);
+}
+
+#[test]
+fn pixel_formats() {
+    use frender::prelude::*;
+
+    assert_eq!(Rgb565.decode(Rgb565.encode(WHITE)), WHITE);
+    assert_eq!(Rgb565.encode(RED), 0xF800);
+    assert_eq!(Gray8.encode(Color::rgb(255, 0, 0)), 76);
+
+    let palette = Indexed::new(vec![TRANSPARENT, BLACK, RED]);
+    let mut canvas = PixelCanvas::new(3, 1, palette);
+    canvas.draw(Line::new(1, 0, 2, 0), Color::rgb(200, 10, 10));
+    canvas.set_pixel(0, 0, Color::rgb(10, 10, 10));
+    assert_eq!(canvas.data(), [1, 2, 2]);
+    assert_eq!(canvas.to_canvas().buffer(), [BLACK, RED, RED]);
+
+    let converted = canvas.convert(Rgb565);
+    assert_eq!(converted.to_bytes(), [0x00, 0x00, 0xF8, 0x00, 0xF8, 0x00]);
+    assert_eq!(converted.convert(Rgba8888).data()[1], [255, 0, 0, 255]);
+
+    // Commands go through the format, the canvas has no `Color` buffer
+    let mut canvas = PixelCanvas::new(4, 3, Rgb565);
+    canvas.background(BLUE);
+    canvas.sub_canvas(Rect::new(1, 1, 2, 2)).line(0, 0, 5, 0, WHITE);
+    assert!(canvas.buffer().is_empty());
+    assert_eq!(canvas.data()[4..8], [0x001F, 0xFFFF, 0xFFFF, 0x001F]);
+
+    canvas.scroll(0, -1, RED);
+    assert_eq!(canvas.data()[..4], [0x001F, 0xFFFF, 0xFFFF, 0x001F]);
+    assert_eq!(canvas.to_buffer()[8..], [RED; 4]);
+
+    canvas.marker(Line::new(0, 1, 3, 1), LIME);
+    canvas.render_markers();
+    assert_eq!(canvas.data()[4..8], [0x07E0; 4]);
+}
+
+#[test]
+fn indexed_palette() {
+    use std::time::Duration;
+    use frender::prelude::*;
+
+    let mut palette = Palette::new(vec![TRANSPARENT, BLACK, RED, LIME, BLUE]);
+    palette.add_cycle(PaletteCycle::new(2..=4, Duration::from_millis(100)));
+
+    let mut canvas = IndexedCanvas::new(4, 1, Indexed::new(palette));
+    canvas.fill(1);
+    canvas.draw(Line::new(1, 0, 3, 0), Color::rgb(0, 250, 0));
+    assert_eq!(canvas.data(), [1, 3, 3, 3]);
+
+    // Cycles only change what is presented
+    assert_eq!(canvas.present().buffer(), [BLACK, LIME, LIME, LIME]);
+    canvas.palette_mut().advance(Duration::from_millis(150));
+    assert_eq!(canvas.present().buffer(), [BLACK, RED, RED, RED]);
+    assert_eq!(canvas.to_canvas().buffer(), [BLACK, LIME, LIME, LIME]);
+    canvas.palette_mut().advance(Duration::from_millis(200));
+    assert_eq!(canvas.palette().resolve(3), LIME);
+
+    // Sprites copy their indices, transparent entries are skipped
+    let mut sprite = IndexedCanvas::new(2, 1, Indexed::new(canvas.palette().clone()));
+    sprite.set_pixel(1, 0, RED);
+    assert_eq!(sprite.data(), [0, 2]);
+
+    canvas.sprite(&sprite, Vec2::new(0, 0));
+    assert_eq!(canvas.data(), [1, 2, 3, 3]);
+
+    // Swapped indices are kept even when another entry has the same color
+    canvas.palette_mut().set_color(4, RED);
+    canvas.sprite_swapped(&sprite, &[0, 1, 4], Vec2::new(2, 0));
+    assert_eq!(canvas.data(), [1, 2, 3, 4]);
+    canvas.sprite_swapped(&sprite, &[0], Vec2::new(-1, 0));
+    assert_eq!(canvas.data(), [2, 2, 3, 4]);
+}
+
+#[test]
+fn layered_canvas() {
+    use frender::prelude::*;
+
+    let mut layers = LayeredCanvas::new(3, 1);
+    layers.add_layer("ui", 10).canvas.background(BLUE);
+    layers.add_layer("background", 0).canvas.background(Color::rgb(200, 100, 0));
+    layers.add_layer("entities", 5).canvas.line(0, 0, 1, 0, RED);
+    let names: Vec<&str> = layers.layers().iter().map(|layer| layer.name()).collect();
+    assert_eq!(names, ["background", "entities", "ui"]);
+
+    // Hidden layers are skipped, but still keep their pixels
+    layers.layer_mut("ui").unwrap().visible = false;
+    assert_eq!(layers.present().buffer(), [RED, RED, Color::rgb(200, 100, 0)]);
+
+    let ui = layers.layer_mut("ui").unwrap();
+    ui.visible = true;
+    ui.opacity = 0.5;
+    assert_eq!(layers.present().pixel(2, 0), Some(Color::rgb(100, 50, 128)));
+
+    let ui = layers.layer_mut("ui").unwrap();
+    ui.opacity = 1.0;
+    ui.blend = BlendMode::Multiply;
+    ui.canvas.background(Color::rgb(255, 255, 0));
+    assert_eq!(layers.present().buffer(), [RED, RED, Color::rgb(200, 100, 0)]);
+
+    layers.layer_mut("ui").unwrap().blend = BlendMode::Screen;
+    assert_eq!(layers.present().pixel(2, 0), Some(Color::rgb(255, 255, 0)));
+
+    // Moving the entities above the ui
+    layers.set_z("entities", 20);
+    layers.layer_mut("ui").unwrap().blend = BlendMode::Normal;
+    assert_eq!(layers.present().buffer(), [RED, RED, Color::rgb(255, 255, 0)]);
+    assert!(layers.remove_layer("entities").is_some());
+    assert_eq!(layers.layers().len(), 2);
+
+    // Compositing does not age the markers, only `tick_markers` does
+    let ui = layers.layer_mut("ui").unwrap();
+    ui.canvas.marker_dyn(Marker::new(MarkerStyle::dot(0, 0), LIME).lifetime(Lifetime::Frames(2)));
+    assert_eq!(layers.present().markers().len(), 1);
+    assert_eq!(layers.present().markers().len(), 1);
+
+    layers.tick_markers();
+    assert_eq!(layers.layer_mut("ui").unwrap().canvas.markers().len(), 1);
+    layers.tick_markers();
+    assert!(layers.present().markers().is_empty());
+}
+
+#[test]
+fn marker_lifetimes() {
+    use std::time::Duration;
+    use frender::prelude::*;
+
+    let mut canvas = CanvasImpl::new(9, 9);
+    canvas.marker(MarkerStyle::dot(4, 4), RED);
+    canvas.marker_dyn(Marker::new(MarkerStyle::cross(4, 4), LIME).lifetime(Lifetime::Frames(2)).category("ai"));
+    canvas.marker_dyn(Marker::new(Line::new(0, 8, 8, 8), BLUE).lifetime(Lifetime::UntilCleared).category("grid"));
+    canvas.marker_dyn(Marker::new(MarkerStyle::label(7, 7, "x"), WHITE).lifetime(Lifetime::Duration(Duration::from_secs(60))));
+
+    canvas.render_markers();
+    assert_eq!(canvas.pixel(4, 4), Some(LIME));
+    assert_eq!(canvas.pixel(4, 2), Some(RED));
+    assert_eq!(canvas.pixel(0, 8), Some(BLUE));
+    assert_eq!(canvas.markers().len(), 3);
+
+    // Hidden categories still age
+    canvas.background(TRANSPARENT);
+    canvas.markers_mut().set_visible("ai", false);
+    canvas.render_markers();
+    assert_eq!(canvas.pixel(4, 4), Some(TRANSPARENT));
+    assert_eq!(canvas.pixel(0, 8), Some(BLUE));
+    assert_eq!(canvas.markers().len(), 2);
+
+    canvas.markers_mut().clear_category("grid");
+    assert_eq!(canvas.markers().len(), 1);
+
+    // Drawing a canvas into another only copies its markers for one frame
+    let mut target = CanvasImpl::new(9, 9);
+    target.image(&canvas, 0, 0, 1);
+    target.image(&canvas, 0, 0, 1);
+    assert_eq!(target.markers().len(), 2);
+    target.render_markers();
+    assert!(target.markers().is_empty());
+    assert_eq!(canvas.markers().len(), 1);
+
+    // Categories hidden on a sub canvas are not given to the parent
+    let mut parent = CanvasImpl::new(9, 9);
+    {
+        let mut sub = parent.sub_canvas(Rect::new(1, 1, 4, 4));
+        sub.marker_dyn(Marker::new(MarkerStyle::dot(0, 0), RED).category("ai"));
+        sub.marker_dyn(Marker::new(MarkerStyle::dot(1, 1), RED).category("grid"));
+        sub.markers_mut().set_visible("ai", false);
+    }
+    assert_eq!(parent.markers().len(), 1);
+    assert_eq!(parent.markers().shown().next().unwrap().category.as_deref(), Some("grid"));
+
+    // Appending leaves the hidden categories behind
+    let mut hidden = Markers::new();
+    hidden.push(Marker::new(MarkerStyle::dot(0, 0), RED).category("ai"));
+    hidden.push(Marker::new(MarkerStyle::dot(0, 0), RED));
+    hidden.set_visible("ai", false);
+    parent.markers_mut().append(&mut hidden);
+    assert_eq!(parent.markers().len(), 2);
+    assert_eq!(hidden.len(), 1);
+
+    // Cropping keeps the hidden categories
+    parent.markers_mut().set_visible("grid", false);
+    parent.resize(4, 4, Anchor::Center);
+    parent.crop(Rect::new(1, 1, 2, 2));
+    assert!(!parent.markers().is_visible("grid"));
+    assert_eq!(parent.markers().len(), 2);
+    assert_eq!(parent.markers().shown().count(), 1);
+
+    let mut arrow = CanvasImpl::new(9, 9);
+    arrow.draw(MarkerStyle::arrow(0, 4, 8, 4), RED.size(2));
+    assert_eq!(arrow.pixel(0, 4), Some(RED));
+    assert_eq!(arrow.pixel(5, 2), Some(RED));
+    assert_eq!(arrow.pixel(5, 6), Some(RED));
+}
+
+#[test]
+fn markers_at_output_resolution() {
+    use frender::prelude::*;
+
+    let mut small = CanvasImpl::new(3, 3);
+    small.background(WHITE);
+    small.marker(Line::new(0, 1, 2, 1), RED.middle());
+    small.marker(MarkerStyle::dot(0, 0), BLUE.size(1));
+
+    // Thin lines through the centers of the upscaled pixels
+    let mut window = CanvasImpl::new(60, 60);
+    window.draw(&small, ImageOption::from(Vec2::new(0, 0)).scaling(20));
+    window.render_markers();
+    assert_eq!(window.pixel(10, 30), Some(RED));
+    assert_eq!(window.pixel(50, 30), Some(RED));
+    assert_eq!(window.pixel(30, 29), Some(WHITE));
+    assert_eq!(window.pixel(30, 31), Some(WHITE));
+    assert_eq!(window.pixel(10, 10), Some(BLUE));
+
+    // Markers follow fractional sizes and flips of the image
+    let mut window = CanvasImpl::new(60, 60);
+    window.draw(&small, ImageOption::from(Rect::new(0, 0, 45, 45)).flip_horizontal());
+    window.render_markers();
+    assert_eq!(window.pixel(37, 7), Some(BLUE));
+    assert_eq!(window.pixel(7, 22), Some(RED));
+    assert_eq!(window.pixel(37, 22), Some(RED));
+    assert_eq!(window.pixel(22, 23), Some(WHITE));
+
+    // Rotated images place the markers on the centers of the turned pixels
+    let mut window = CanvasImpl::new(60, 60);
+    window.draw(&small, ImageOption::from(Vec2::new(0, 0)).scaling(20).rotate(std::f32::consts::FRAC_PI_4));
+    window.render_markers();
+    assert_eq!(window.pixel(30, 2), Some(BLUE));
+    assert_eq!(window.pixel(16, 16), Some(RED));
+    assert_eq!(window.pixel(30, 30), Some(RED));
+    assert_eq!(window.pixel(44, 44), Some(RED));
+    assert_eq!(window.pixel(31, 30), Some(WHITE));
+}
+
+#[test]
+fn builtin_text() {
+    use frender::prelude::*;
+
+    // '!' is a vertical bar on rows 1 to 5 and a dot on row 7, in the third column
+    let mut canvas = CanvasImpl::new(12, 20);
+    canvas.text(0, 0, "!\n!", RED);
+    assert_eq!(canvas.pixel(2, 1), Some(RED));
+    assert_eq!(canvas.pixel(2, 6), Some(TRANSPARENT));
+    assert_eq!(canvas.pixel(2, 7), Some(RED));
+    assert_eq!(canvas.pixel(2, 11), Some(RED));
+    assert_eq!(canvas.pixel(1, 1), Some(TRANSPARENT));
+
+    // Scaling and the view transform both apply
+    let mut canvas = CanvasImpl::new(20, 30);
+    let mut context = canvas.get_context();
+    *context.view_mut().transform_mut() = Some(Transform2D::new(Vec2::new(2, 0), Vec2::new(2, 2)));
+    context.text(0, 0, "!", BLUE.scaling(1));
+    assert_eq!(canvas.pixel(6, 2), Some(BLUE));
+    assert_eq!(canvas.pixel(7, 3), Some(BLUE));
+    assert_eq!(canvas.pixel(8, 2), Some(TRANSPARENT));
+    assert_eq!(canvas.pixel(6, 12), Some(TRANSPARENT));
+    assert_eq!(canvas.pixel(6, 14), Some(BLUE));
+
+    // A zero scaling draws nothing
+    let mut canvas = CanvasImpl::new(12, 20);
+    canvas.text(0, 0, "!", RED.scaling(0));
+    canvas.draw(Text::new_box(Rect::new(0, 0, 12, 20), "!"), RED.scaling(0));
+    assert!(canvas.buffer().iter().all(|color| *color == TRANSPARENT));
+
+    // Flips mirror the text inside of the space it takes, negative view scalings are flips
+    canvas.text(0, 0, "!", RED.flip_horizontal());
+    assert_eq!(canvas.pixel(3, 1), Some(RED));
+    assert_eq!(canvas.pixel(2, 1), Some(TRANSPARENT));
+
+    let mut canvas = CanvasImpl::new(12, 20);
+    let mut context = canvas.get_context();
+    *context.view_mut().transform_mut() = Some(Transform2D::new(Vec2::new(12, 10), Vec2::new(-1, -1)));
+    context.text(0, 0, "!", BLUE);
+    assert_eq!(canvas.pixel(9, 8), Some(BLUE));
+    assert_eq!(canvas.pixel(9, 2), Some(BLUE));
+    assert_eq!(canvas.pixel(2, 1), Some(TRANSPARENT));
+}
+
+#[test]
+fn truetype_invalid_bytes() {
+    use frender::prelude::*;
+
+    assert!(matches!(TrueTypeFont::from_bytes(Vec::new(), 16.0), Err(FontError::Invalid(_))));
+    assert!(matches!(TrueTypeFont::from_bytes(b"not a font at all".to_vec(), 16.0), Err(FontError::Invalid(_))));
+
+    // A TrueType header that claims tables which are not there
+    let mut truncated = vec![0x00, 0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x40, 0x00, 0x02, 0x00, 0x00];
+    truncated.extend(b"head");
+    assert!(matches!(TrueTypeFont::from_bytes(truncated, 16.0), Err(FontError::Invalid(_))));
+
+    assert!(matches!(TrueTypeFont::from_path("does/not/exist.ttf", 16.0), Err(FontError::Io(_))));
+}
+
+#[test]
+fn bitmap_font_files() {
+    use frender::prelude::*;
+
+    let bdf = "STARTFONT 2.1
+FONTBOUNDINGBOX 3 4 0 -1
+FONT_ASCENT 3
+FONT_DESCENT 1
+CHARS 2
+STARTCHAR space
+ENCODING 32
+DWIDTH 4 0
+BBX 0 0 0 0
+BITMAP
+ENDCHAR
+STARTCHAR A
+ENCODING 65
+DWIDTH 4 0
+BBX 3 3 0 0
+BITMAP
+40
+A0
+E0
+ENDCHAR
+ENDFONT
+";
+    let font = BitmapFont::from_bdf(bdf).unwrap();
+    assert_eq!((font.line_height(), font.ascent()), (4, 3));
+    assert_eq!(font.glyph(' ').unwrap().advance(), 4);
+    assert_eq!(font.glyph(' ').unwrap().image().size(), (0, 0));
+
+    let a = font.glyph('A').unwrap();
+    assert_eq!(a.image().size(), (3, 3));
+    assert_eq!(a.image().pixel(1, 0), Some(WHITE));
+    assert_eq!(a.image().pixel(1, 1), Some(TRANSPARENT));
+    assert!(BitmapFont::from_bdf("STARTFONT 2.1\nENDFONT\n").is_err());
+
+    // PSF1 with 256 glyphs of 8x2, the index is the code point
+    let mut psf1 = vec![0x36, 0x04, 0x00, 0x02];
+    psf1.extend(vec![0; 256 * 2]);
+    psf1[4 + 'A' as usize * 2] = 0x81;
+    let font = BitmapFont::from_psf(&psf1).unwrap();
+    let a = font.glyph('A').unwrap();
+    assert_eq!(a.image().size(), (8, 2));
+    assert_eq!(a.image().pixel(0, 0), Some(WHITE));
+    assert_eq!(a.image().pixel(7, 0), Some(WHITE));
+    assert_eq!(a.image().pixel(0, 1), Some(TRANSPARENT));
+
+    assert!(BitmapFont::from_psf(&psf1[..100]).is_err());
+    assert!(BitmapFont::from_psf(&[0x36, 0x04, 0x00, 0x00]).is_err());
+
+    // PSF2 header claiming zero wide glyphs
+    let header = [0x864ab572u32, 0, 32, 0, 1, 0, 8, 0];
+    let psf2: Vec<u8> = header.iter().flat_map(|value| value.to_le_bytes()).collect();
+    assert!(BitmapFont::from_psf(&psf2).is_err());
+
+    // BMFont descriptor with an opaque page next to it, a red glyph and a gray one
+    let directory = std::env::temp_dir().join(format!("frender-bmfont-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let page = image::RgbaImage::from_raw(4, 2, [[255, 0, 0, 255], [255, 0, 0, 255], [64, 64, 64, 255], [0, 0, 0, 255]].repeat(2).concat()).unwrap();
+    page.save(directory.join("page.png")).unwrap();
+    let fnt = "info face=\"Test Font\" size=2
+common lineHeight=3 base=2 pages=1
+page id=0 file=\"page.png\"
+chars count=2
+char id=65 x=0 y=0 width=2 height=2 xoffset=0 yoffset=1 xadvance=3 page=0
+char id=66 x=2 y=0 width=2 height=2 xoffset=0 yoffset=0 xadvance=2 page=0
+kerning first=65 second=66 amount=-1
+";
+    std::fs::write(directory.join("font.fnt"), fnt).unwrap();
+    let path = directory.join("font.fnt");
+
+    let font = BitmapFont::from_bmfont_path(path.to_str().unwrap(), PageColors::Keep).unwrap();
+    assert_eq!((font.line_height(), font.ascent()), (3, 2));
+    let a = font.glyph('A').unwrap();
+    assert_eq!((a.offset(), a.advance()), (Vec2::new(0, 1), 3));
+    assert_eq!(a.image().pixel(1, 1), Some(RED));
+    assert_eq!(font.glyph('B').unwrap().image().pixel(0, 0), Some(Color::rgb(64, 64, 64)));
+    assert_eq!(font.kerning('A', 'B'), -1);
+
+    // Brightness pages are turned into white glyphs with the brightness as alpha
+    let font = BitmapFont::from_bmfont_path(path.to_str().unwrap(), PageColors::Brightness).unwrap();
+    assert_eq!(font.glyph('A').unwrap().image().pixel(0, 0), Some(WHITE));
+    assert_eq!(font.glyph('B').unwrap().image().pixel(0, 0), Some(WHITE.with_alpha(64)));
+    assert_eq!(font.glyph('B').unwrap().image().pixel(1, 0), Some(WHITE.with_alpha(0)));
+
+    std::fs::write(directory.join("broken.fnt"), "char id=65 x=0 y=0 width=1 height=1\n").unwrap();
+    assert!(matches!(BitmapFont::from_bmfont_path(directory.join("broken.fnt").to_str().unwrap(), PageColors::Keep), Err(FontError::Invalid(_))));
+    assert!(matches!(BitmapFont::from_bmfont_path(directory.join("missing.fnt").to_str().unwrap(), PageColors::Keep), Err(FontError::Io(_))));
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn gradient_paints() {
+    use frender::prelude::*;
+
+    let gray = Color::rgb(128, 128, 128);
+    let linear = Gradient::linear(Vec2::new(0, 0), Vec2::new(10, 0)).stop(0.0, BLACK).stop(1.0, WHITE);
+    assert_eq!(linear.color_at(-5.0, 0.0), BLACK);
+    assert_eq!(linear.color_at(5.0, 3.0), gray);
+    assert_eq!(linear.color_at(15.0, 0.0), WHITE);
+
+    let repeat = linear.clone().spread(Spread::Repeat);
+    assert_eq!(repeat.color_at(15.0, 0.0), gray);
+    let reflect = linear.clone().spread(Spread::Reflect);
+    assert_eq!(reflect.color_at(12.5, 0.0), reflect.color_at(7.5, 0.0));
+    assert_eq!(reflect.color_at(20.0, 0.0), BLACK);
+
+    let radial = Gradient::radial(Vec2::new(0, 0), 10).stop(0.0, RED).stop(1.0, BLUE);
+    assert_eq!(radial.color_at(0.0, 0.0), RED);
+    assert_eq!(radial.color_at(0.0, -20.0), BLUE);
+
+    let conic = Gradient::conic(Vec2::new(0, 0), 0.0).stop(0.0, RED).stop(0.5, LIME).stop(1.0, BLUE);
+    assert_eq!(conic.color_at(1.0, 0.0), RED);
+    assert_eq!(conic.color_at(-1.0, 0.0), LIME);
+
+    // The gradient moves and scales with the shape
+    let mut moved = linear.clone();
+    moved.transform(&Transform2D::new(Vec2::new(10, 0), Vec2::new(2, 2)));
+    assert_eq!(moved.color_at(20.0, 0.0), gray);
+
+    let mut canvas = CanvasImpl::new(20, 20);
+    let fill = Gradient::linear(Vec2::new(2, 0), Vec2::new(18, 0)).stop(0.0, RED).stop(1.0, BLUE);
+    canvas.circle(10, 10, 8, WHITE.fill(fill));
+    let (left, right) = (canvas.pixel(3, 10).unwrap(), canvas.pixel(16, 10).unwrap());
+    assert!(left.r() > left.b() && right.b() > right.r());
+    assert_eq!(canvas.pixel(10, 2), Some(WHITE));
+
+    // Non-uniform scaling stretches radial gradients into ellipses
+    let mut ellipse = radial.clone();
+    ellipse.transform(&Transform2D::new(Vec2::new(0, 0), Vec2::new(2, 1)));
+    assert_eq!(ellipse.color_at(10.0, 0.0), ellipse.color_at(0.0, 5.0));
+    assert_eq!(ellipse.color_at(20.0, 0.0), BLUE);
+    assert_ne!(ellipse.color_at(0.0, 9.0), BLUE);
+
+    // Flipped conic gradients sweep the other way
+    for scaling in [Vec2::new(-1, 1), Vec2::new(1, -1)] {
+        let mut flipped = conic.clone();
+        flipped.transform(&Transform2D::new(Vec2::new(0, 0), scaling));
+        let (sx, sy) = (*scaling.x() as f32, *scaling.y() as f32);
+        for (x, y) in [(1.0, 0.0), (0.0, 1.0), (3.0, -1.0), (-2.0, -3.0)] {
+            assert_eq!(flipped.color_at(x * sx, y * sy), conic.color_at(x, y));
+        }
+    }
+
+    // Semi-transparent paints are drawn over the canvas, once per pixel
+    let half = RED.with_alpha(128);
+    let expected = ColorSpace::default().over(half, BLUE);
+    for fill in [Paint::from(half), Paint::from(Gradient::linear(Vec2::new(0, 0), Vec2::new(20, 0)).stop(0.0, half).stop(1.0, half))] {
+        let mut canvas = CanvasImpl::new(20, 20);
+        canvas.background(BLUE);
+        canvas.circle(10, 10, 8, WHITE.fill(fill));
+        assert_eq!(canvas.pixel(10, 10), Some(expected));
+        assert_eq!(canvas.pixel(4, 10), Some(expected));
+        assert_eq!(canvas.pixel(10, 2), Some(WHITE));
+    }
+}
+
+#[test]
+fn texture_paints() {
+    use frender::prelude::*;
+    use std::rc::Rc;
+
+    let image = Rc::new(CanvasImpl::new_buffer(2, 2, vec![RED, LIME, BLUE, WHITE]));
+    let texture = Texture::new(Rc::clone(&image));
+    assert_eq!(texture.color_at(1, 1), WHITE);
+    assert_eq!(texture.color_at(2, 0), RED);
+    assert_eq!(texture.color_at(-1, -1), WHITE);
+
+    let clamp = texture.clone().wrap(Wrap::Clamp);
+    assert_eq!(clamp.color_at(5, 0), LIME);
+    assert_eq!(clamp.color_at(-3, 7), BLUE);
+
+    let mirror = texture.clone().wrap(Wrap::Mirror);
+    assert_eq!(mirror.color_at(2, 0), LIME);
+    assert_eq!(mirror.color_at(3, 0), RED);
+
+    // Each texture pixel covers 2x2 canvas pixels starting at (1, 1)
+    let placed = texture.clone().placement(Transform2D::new(Vec2::new(1, 1), Vec2::new(2, 2)));
+    assert_eq!(placed.color_at(2, 2), RED);
+    assert_eq!(placed.color_at(3, 2), LIME);
+    assert_eq!(placed.color_at(0, 0), WHITE);
+
+    // Transforming the paint moves and scales the texture with the shape
+    let mut paint = Paint::from(texture.clone());
+    paint.transform(&Transform2D::new(Vec2::new(1, 1), Vec2::new(2, 2)));
+    assert_eq!(paint.color_at(3, 2), LIME);
+
+    let mut moved = texture.clone();
+    moved.transform(&Transform2D::new(Vec2::new(1, 1), Vec2::new(2, 2)));
+    assert_eq!(moved.color_at(3, 2), LIME);
+
+    let checkerboard = Texture::checkerboard(2, BLACK, WHITE);
+    assert_eq!(checkerboard.color_at(1, 1), BLACK);
+    assert_eq!(checkerboard.color_at(2, 1), WHITE);
+    assert_eq!(checkerboard.color_at(2, 2), BLACK);
+
+    let hatch = Texture::hatch(Hatch::Vertical, 3, GRAY);
+    assert_eq!(hatch.color_at(3, 1), GRAY);
+    assert_eq!(hatch.color_at(4, 1), TRANSPARENT);
+    let cross = Texture::hatch(Hatch::Cross, 3, GRAY);
+    assert_eq!(cross.color_at(1, 3), GRAY);
+    assert_eq!(cross.color_at(1, 1), TRANSPARENT);
+    let dots = Texture::dots(4, GRAY);
+    assert_eq!(dots.color_at(4, 8), GRAY);
+    assert_eq!(dots.color_at(4, 9), TRANSPARENT);
+
+    // The transparent pixels of a pattern keep what is below
+    let mut canvas = CanvasImpl::new(6, 6);
+    canvas.background(BLACK);
+    canvas.flood_fill(0, 0, RED.paint(Texture::hatch(Hatch::Horizontal, 2, LIME)));
+    assert_eq!(canvas.pixel(3, 0), Some(LIME));
+    assert_eq!(canvas.pixel(3, 1), Some(BLACK));
+    assert_eq!(canvas.pixel(3, 4), Some(LIME));
+
+    let mut canvas = CanvasImpl::new(12, 12);
+    canvas.circle(6, 6, 5, WHITE.fill(Texture::checkerboard(1, RED, BLUE)));
+    assert_eq!(canvas.pixel(6, 6), Some(RED));
+    assert_eq!(canvas.pixel(7, 6), Some(BLUE));
+    assert_eq!(canvas.pixel(0, 0), Some(TRANSPARENT));
+}
+
+#[test]
+fn image_resampling() {
+    use frender::prelude::*;
+
+    let gray = Color::rgb(128, 128, 128);
+
+    // Scaling replaces a previous size
+    let image = CanvasImpl::new_buffer(2, 2, vec![RED; 4]);
+    let mut canvas = CanvasImpl::new(8, 8);
+    canvas.draw(&image, ImageOption::from(Vec2::new(0, 0)).size(8.0, 8.0).scaling(2));
+    assert_eq!(canvas.pixel(3, 3), Some(RED));
+    assert_eq!(canvas.pixel(4, 4), Some(TRANSPARENT));
+
+    // 1.5x bilinear: the edges keep the source colors, the middle pixel is halfway between them
+    let image = CanvasImpl::new_buffer(2, 1, vec![BLACK, WHITE]);
+    let mut canvas = CanvasImpl::new(3, 1);
+    canvas.draw(&image, ImageOption::from(Vec2::new(0, 0)).scale(1.5, 1.0).filter(Filter::Bilinear).color_space(ColorSpace::Srgb));
+    assert_eq!(canvas.buffer(), &[BLACK, gray, WHITE]);
+
+    // 0.5x box: each destination pixel is the average of the 2x2 block it covers
+    let image = CanvasImpl::new_buffer(4, 2, vec![BLACK, WHITE, RED, RED, WHITE, BLACK, RED, RED]);
+    let mut canvas = CanvasImpl::new(2, 1);
+    canvas.draw(&image, ImageOption::from(Vec2::new(0, 0)).scale(0.5, 0.5).filter(Filter::Box).color_space(ColorSpace::Srgb));
+    assert_eq!(canvas.buffer(), &[gray, RED]);
+}